@@ -1,4 +1,5 @@
 use super::AOC2015;
+use aoc_runner::bitgrid::BitGrid;
 use aoc_runner::point2d::Point2D;
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use nom::sequence::delimited;
@@ -19,8 +20,11 @@ use anyhow::Result;
 
 #[derive(Clone, Debug, PartialEq, Display)]
 pub enum InstructionType {
+    #[strum(serialize = "turn on")]
     On,
+    #[strum(serialize = "turn off")]
     Off,
+    #[strum(serialize = "toggle")]
     Toggle,
 }
 
@@ -45,8 +49,8 @@ impl Display for Instruction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} {} through {}",
-            self.instruction_type, self.start, self.end
+            "{} {},{} through {},{}",
+            self.instruction_type, self.start.x, self.start.y, self.end.x, self.end.y
         )
     }
 }
@@ -90,22 +94,20 @@ impl Solution<'_, { Day::Day6 }, { Part::One }> for AOC2015<{ Day::Day6 }> {
     type Output = usize;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let mut state = [[false; 1000]; 1000];
-        for instruction in input {
-            for i in instruction.start.x..(instruction.end.x + 1) {
-                for j in instruction.start.y..(instruction.end.y + 1) {
-                    state[i][j] = match instruction.instruction_type {
-                        InstructionType::On => true,
-                        InstructionType::Off => false,
-                        InstructionType::Toggle => !state[i][j],
-                    }
-                }
+        let mut state = BitGrid::new(1000, 1000);
+        for Instruction {
+            instruction_type,
+            start,
+            end,
+        } in input
+        {
+            match instruction_type {
+                InstructionType::On => state.fill_rect(*start, *end, true),
+                InstructionType::Off => state.fill_rect(*start, *end, false),
+                InstructionType::Toggle => state.toggle_rect(*start, *end),
             }
         }
-        Ok(state
-            .iter()
-            .flat_map(|row| row.iter().filter(|light| **light))
-            .count())
+        Ok(state.count_ones())
     }
 }
 
@@ -133,8 +135,34 @@ impl Solution<'_, { Day::Day6 }, { Part::Two }> for AOC2015<{ Day::Day6 }> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_runner::parse::parse_all;
+    use aoc_runner::roundtrip;
+    use aoc_runner::testing::{Arbitrary, Rng};
     use aoc_runner::PartOneVerifier;
 
+    impl Arbitrary for Instruction {
+        fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+            let instruction_type = rng
+                .choose(&[
+                    InstructionType::On,
+                    InstructionType::Off,
+                    InstructionType::Toggle,
+                ])
+                .clone();
+            Instruction {
+                instruction_type,
+                start: Point2D::arbitrary(rng, size),
+                end: Point2D::arbitrary(rng, size),
+            }
+        }
+    }
+
+    roundtrip!(test_roundtrip, Instruction::arbitrary, |s| parse_all(
+        Instruction::parse,
+        s
+    )
+    .ok());
+
     #[test]
     fn test() -> Result<()> {
         let problem = super::AOC2015::<{ Day::Day6 }>;
@@ -1,5 +1,6 @@
 use super::AOC2015;
 use anyhow::{Context, Result};
+use aoc_runner::memo::Memo;
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use nom::{
     branch::alt,
@@ -122,35 +123,32 @@ impl ParseInput<'_, { Day::Day7 }> for AOC2015<{ Day::Day7 }> {
     }
 }
 
-fn eval_wire(
-    wire: &WireName,
-    input: &HashMap<WireName, Operation>,
-    cache: &mut HashMap<WireName, SignalValue>,
-) -> Result<SignalValue> {
-    if cache.contains_key(wire) {
-        return Ok(cache[wire]);
-    }
-    let val = match input.get(wire).context(format!("Missing {wire}"))? {
-        Operation::Literal(operand) => eval_operand(operand, input, cache)?,
-        Operation::Not(wire) => !eval_operand(wire, input, cache)?,
-        Operation::Or(l, r) => eval_operand(l, input, cache)? | eval_operand(r, input, cache)?,
-        Operation::And(l, r) => eval_operand(l, input, cache)? & eval_operand(r, input, cache)?,
-        Operation::LShift(wire, bits) => eval_operand(wire, input, cache)? << bits,
-        Operation::RShift(wire, bits) => eval_operand(wire, input, cache)? >> bits,
-    };
-    cache.insert(wire.clone(), val);
-    Ok(val)
+type Wires = HashMap<WireName, Operation>;
+type Signals = Memo<WireName, SignalValue>;
+
+fn eval_wire(wire: &WireName, input: &Wires, cache: &mut Signals) -> Result<SignalValue> {
+    cache.solve(wire.clone(), |cache, wire| {
+        let mut eval_operand = |operand: &Operand| match operand {
+            Operand::Wire(wire) => cache.get(wire.clone()),
+            Operand::Signal(num) => Ok(*num),
+        };
+        Ok(match input.get(wire).context(format!("Missing {wire}"))? {
+            Operation::Literal(operand) => eval_operand(operand)?,
+            Operation::Not(wire) => !eval_operand(wire)?,
+            Operation::Or(l, r) => eval_operand(l)? | eval_operand(r)?,
+            Operation::And(l, r) => eval_operand(l)? & eval_operand(r)?,
+            Operation::LShift(wire, bits) => eval_operand(wire)? << bits,
+            Operation::RShift(wire, bits) => eval_operand(wire)? >> bits,
+        })
+    })
 }
 
-fn eval_operand(
-    operand: &Operand,
-    input: &HashMap<WireName, Operation>,
-    cache: &mut HashMap<WireName, SignalValue>,
-) -> Result<SignalValue> {
-    match operand {
-        Operand::Wire(wire) => eval_wire(wire, input, cache),
-        Operand::Signal(num) => Ok(*num),
-    }
+fn wires(input: &[Instruction]) -> Wires {
+    input
+        .iter()
+        .cloned()
+        .map(|instruction| (instruction.wire, instruction.operation))
+        .collect()
 }
 
 impl Solution<'_, { Day::Day7 }, { Part::One }> for AOC2015<{ Day::Day7 }> {
@@ -158,14 +156,7 @@ impl Solution<'_, { Day::Day7 }, { Part::One }> for AOC2015<{ Day::Day7 }> {
     type Output = SignalValue;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let input: HashMap<WireName, Operation> = input
-            .clone()
-            .into_iter()
-            .map(|instruction| (instruction.wire, instruction.operation))
-            .collect();
-
-        let mut cache = HashMap::new();
-        eval_wire(&"a".to_string(), &input, &mut cache)
+        eval_wire(&"a".to_string(), &wires(input), &mut Memo::new())
     }
 }
 
@@ -174,18 +165,12 @@ impl Solution<'_, { Day::Day7 }, { Part::Two }> for AOC2015<{ Day::Day7 }> {
     type Output = SignalValue;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let mut input: HashMap<WireName, Operation> = input
-            .clone()
-            .into_iter()
-            .map(|instruction| (instruction.wire, instruction.operation))
-            .collect();
-
+        let input = wires(input);
         let a = "a".to_string();
-        let b = "b".to_string();
-        let mut cache = HashMap::new();
-        let a_signal = eval_wire(&a, &input, &mut cache)?;
-        input.insert(b, Operation::Literal(Operand::Signal(a_signal)));
-        cache.clear();
+        let a_signal = eval_wire(&a, &input, &mut Memo::new())?;
+        // Override wire b with the first signal on a and rerun from scratch.
+        let mut cache = Memo::new();
+        cache.insert("b".to_string(), a_signal);
         eval_wire(&a, &input, &mut cache)
     }
 }
@@ -222,11 +207,8 @@ y RSHIFT 2 -> g
 NOT x -> h
 NOT y -> i";
         let parsed: Vec<Instruction> = problem.parse_input(input)?;
-        let input: HashMap<WireName, Operation> = parsed
-            .into_iter()
-            .map(|instruction| (instruction.wire, instruction.operation))
-            .collect();
-        let mut cache = HashMap::new();
+        let input = wires(&parsed);
+        let mut cache = Memo::new();
 
         assert_eq!(eval_wire(&"d".to_string(), &input, &mut cache)?, 72);
         assert_eq!(eval_wire(&"e".to_string(), &input, &mut cache)?, 507);
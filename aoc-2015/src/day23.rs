@@ -0,0 +1,108 @@
+use super::AOC2015;
+use anyhow::Result;
+use aoc_runner::parse::{lines_of, parse_all, signed};
+use aoc_runner::vm::{Control, Instruction, Machine, State, Word};
+use aoc_runner::{Day, ParseInput, Part, Solution};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::alpha1,
+    combinator::map,
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+type Register = String;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Hlf(Register),
+    Tpl(Register),
+    Inc(Register),
+    Jmp(Word),
+    Jie(Register, Word),
+    Jio(Register, Word),
+}
+
+fn register(input: &str) -> IResult<&str, Register> {
+    map(alpha1, str::to_string)(input)
+}
+
+impl Op {
+    fn parse(input: &str) -> IResult<&str, Op> {
+        let conditional = |input| separated_pair(register, tag(", "), signed)(input);
+        alt((
+            map(preceded(tag("hlf "), register), Op::Hlf),
+            map(preceded(tag("tpl "), register), Op::Tpl),
+            map(preceded(tag("inc "), register), Op::Inc),
+            map(preceded(tag("jmp "), signed), Op::Jmp),
+            map(preceded(tag("jie "), conditional), |(r, offset)| {
+                Op::Jie(r, offset)
+            }),
+            map(preceded(tag("jio "), conditional), |(r, offset)| {
+                Op::Jio(r, offset)
+            }),
+        ))(input)
+    }
+}
+
+impl Instruction for Op {
+    fn execute(&self, state: &mut State) -> Control {
+        match self {
+            Op::Hlf(r) => state.update(r, |v| v / 2),
+            Op::Tpl(r) => state.update(r, |v| v * 3),
+            Op::Inc(r) => state.update(r, |v| v + 1),
+            Op::Jmp(offset) => return Control::Jump(*offset),
+            Op::Jie(r, offset) if state.get(r) % 2 == 0 => return Control::Jump(*offset),
+            Op::Jio(r, offset) if state.get(r) == 1 => return Control::Jump(*offset),
+            Op::Jie(..) | Op::Jio(..) => (),
+        }
+        Control::Next
+    }
+}
+
+impl ParseInput<'_, { Day::Day23 }> for AOC2015<{ Day::Day23 }> {
+    type Parsed = Vec<Op>;
+
+    fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
+        parse_all(lines_of(Op::parse), input)
+    }
+}
+
+fn run(program: &[Op], a: Word) -> Word {
+    let mut machine = Machine::new(program.to_vec()).with_register("a", a);
+    machine.run();
+    machine.register("b")
+}
+
+impl Solution<'_, { Day::Day23 }, { Part::One }> for AOC2015<{ Day::Day23 }> {
+    type Input = Vec<Op>;
+    type Output = Word;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        Ok(run(input, 0))
+    }
+}
+
+impl Solution<'_, { Day::Day23 }, { Part::Two }> for AOC2015<{ Day::Day23 }> {
+    type Input = Vec<Op>;
+    type Output = Word;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        Ok(run(input, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_runner::PartOneVerifier;
+    use aoc_runner::PartTwoVerifier;
+
+    #[test]
+    fn test() -> Result<()> {
+        let problem = super::AOC2015::<{ Day::Day23 }>;
+        problem.test_part1("inc b\njio b, +2\ntpl b\ninc b\n", 2)?;
+        problem.test_part2("jio a, +2\ninc b\ninc b\n", 1)
+    }
+}
@@ -10,21 +10,22 @@ mod day04;
 mod day05;
 mod day06;
 mod day07;
+mod day23;
 
 use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
+use aoc_runner::input::read_input;
 use aoc_runner::run_solutions;
 use aoc_runner::Day;
 use aoc_runner::SolutionRunner;
-use std::fs::read_to_string;
 
 pub struct AOC2015<const DAY: Day>;
 
 fn input(day: &Day) -> Result<String> {
     let day: u8 = (*day).into();
     let path = format!("aoc-2015/input/day{day}.txt");
-    read_to_string(&path).context(path)
+    read_input(&path).context(path)
 }
 
 fn solve(day: &Day) -> Result<()> {
@@ -37,6 +38,7 @@ fn solve(day: &Day) -> Result<()> {
         Day::Day5 => AOC2015::<{ Day::Day5 }>.run(&input),
         Day::Day6 => AOC2015::<{ Day::Day6 }>.run(&input),
         Day::Day7 => AOC2015::<{ Day::Day7 }>.run(&input),
+        Day::Day23 => AOC2015::<{ Day::Day23 }>.run(&input),
         _ => Err(anyhow!("Day not implemented yet")),
     }
 }
@@ -2,10 +2,10 @@ use super::AOC2021;
 use anyhow::Result;
 use anyhow::{anyhow, Context};
 use aoc_runner::{Day, ParseInput, Part, Solution};
-use itertools::Itertools;
 use std::{collections::HashSet, str::FromStr};
 
 use aoc_runner::point2d::Point2D;
+use aoc_runner::sparse::SparseGrid;
 
 enum FoldInstruction {
     X(usize),
@@ -92,28 +92,19 @@ fn fold(points: &mut Points, instruction: &FoldInstruction) {
 }
 
 fn print_paper(points: &Points) -> Result<String> {
-    let max_x = points
+    let paper: SparseGrid<()> = points
         .iter()
-        .map(|p| p.x)
-        .max()
-        .context("need a point after folding")?;
-
-    let max_y = points
-        .iter()
-        .map(|p| p.y)
-        .max()
-        .context("need a point after folding")?;
-    let mut output: Vec<Vec<char>> = vec![vec!['.'; max_x + 1]; max_y + 1];
-    for point in points {
-        output[point.y][point.x] = '#';
-    }
-    Ok(format!(
-        "\n{}",
-        output
-            .into_iter()
-            .map(|row| row.into_iter().collect::<String>())
-            .join("\n")
-    ))
+        .map(|point| Ok((point.try_convert()?, ())))
+        .collect::<Result<_>>()?;
+    let (_, max) = paper.bounds().context("need a point after folding")?;
+    let picture = paper.render_region(Point2D::new(0, 0), max, |cell| {
+        if cell.is_some() {
+            '#'
+        } else {
+            '.'
+        }
+    });
+    Ok(format!("\n{picture}"))
 }
 
 impl Solution<'_, { Day::Day13 }, { Part::One }> for AOC2021<{ Day::Day13 }> {
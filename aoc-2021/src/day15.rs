@@ -1,13 +1,13 @@
 use super::AOC2021;
 use anyhow::{Context, Result};
+use aoc_runner::point2d::Point2D;
+use aoc_runner::viz::{self, Colour, Frame};
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use core::panic;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::collections::{BinaryHeap, HashMap};
 
-use colored::Colorize;
-
 type Point = (usize, usize);
 
 impl ParseInput<'_, { Day::Day15 }> for AOC2021<{ Day::Day15 }> {
@@ -114,19 +114,21 @@ fn djikstra(map: &[Vec<u32>]) -> Option<(HashSet<Point>, u32)> {
     None
 }
 
-#[allow(dead_code)]
-fn print_map(map: &[Vec<u32>], prev: &HashSet<Point>) {
-    let size = map.len();
-    for (i, row) in map.iter().enumerate().take(size) {
-        for (j, elem) in row.iter().enumerate().take(size) {
-            if prev.contains(&(i, j)) {
-                print!("{}", elem.to_string().bright_red());
+/// The cave's risk levels with the lowest risk path highlighted.
+fn path_frame(map: &[Vec<u32>], path: &HashSet<Point>) -> Frame {
+    let mut frame = Frame::new(map.len(), map.len());
+    for (i, row) in map.iter().enumerate() {
+        for (j, &risk) in row.iter().enumerate() {
+            let colour = if path.contains(&(i, j)) {
+                Colour::Red
             } else {
-                print!("{elem}");
-            }
+                Colour::Default
+            };
+            let risk = char::from_digit(risk, 10).unwrap_or('?');
+            frame.set(Point2D::new(j, i), risk, colour);
         }
-        println!();
     }
+    frame
 }
 
 impl Solution<'_, { Day::Day15 }, { Part::One }> for AOC2021<{ Day::Day15 }> {
@@ -134,8 +136,8 @@ impl Solution<'_, { Day::Day15 }, { Part::One }> for AOC2021<{ Day::Day15 }> {
     type Output = u32;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let (_prev, path_cost) = djikstra(input).context("Did not find solution")?;
-        //print_map(input, &prev);
+        let (path, path_cost) = djikstra(input).context("Did not find solution")?;
+        viz::emit(|| path_frame(input, &path));
         Ok(path_cost)
     }
 }
@@ -173,8 +175,8 @@ impl Solution<'_, { Day::Day15 }, { Part::Two }> for AOC2021<{ Day::Day15 }> {
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
         let input = part2_extend(input.to_vec());
-        let (_prev, path_cost) = djikstra(&input).context("Did not find solution")?;
-        //print_map(&input, &prev);
+        let (path, path_cost) = djikstra(&input).context("Did not find solution")?;
+        viz::emit(|| path_frame(&input, &path));
         Ok(path_cost)
     }
 }
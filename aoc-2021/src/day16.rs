@@ -1,91 +1,9 @@
 use anyhow::{Context, Result};
-use nom::bytes::complete::take_while_m_n;
-use nom::combinator::map_res;
-use nom::multi::many1;
-use nom::IResult;
 use num_enum::TryFromPrimitive;
 
 use super::AOC2021;
+use aoc_runner::bits::{hex_to_bytes, BitReader};
 use aoc_runner::{Day, ParseInput, Part, Solution};
-use std::iter::Iterator;
-use std::str::FromStr;
-
-#[derive(Clone)]
-pub struct BitIterator {
-    buf: Vec<u8>,
-    index: usize,
-    bit_index: u8,
-    num_remaining: usize,
-}
-
-fn from_hex(input: &str) -> Result<u8, std::num::ParseIntError> {
-    u8::from_str_radix(input, 16)
-}
-
-fn is_hex_digit(c: char) -> bool {
-    c.is_ascii_hexdigit()
-}
-
-fn hex2(input: &str) -> IResult<&str, u8> {
-    map_res(take_while_m_n(2, 2, is_hex_digit), from_hex)(input)
-}
-
-fn hex_to_array(input: &str) -> IResult<&str, Vec<u8>> {
-    many1(hex2)(input)
-}
-
-impl FromStr for BitIterator {
-    type Err = anyhow::Error;
-    fn from_str(s: &str) -> Result<Self> {
-        let s = s.to_string();
-        let (_remainder, buf) = hex_to_array(&s).unwrap();
-        let len = buf.len();
-        Ok(BitIterator {
-            buf,
-            index: 0,
-            bit_index: 7,
-            num_remaining: len * 8,
-        })
-    }
-}
-
-impl Iterator for BitIterator {
-    type Item = bool; // single bit
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.num_remaining == 0 || self.index >= self.buf.len() {
-            return None;
-        }
-        let ret = (self.buf[self.index] & (0b1 << self.bit_index)) != 0;
-        if self.bit_index == 0 {
-            self.index += 1;
-            self.bit_index = 7
-        } else {
-            self.bit_index -= 1;
-        }
-        self.num_remaining -= 1;
-        Some(ret)
-    }
-}
-
-impl BitIterator {
-    fn read(&mut self, bits: u8) -> Option<u8> {
-        let mut ret = 0;
-        for i in 0..bits {
-            let bit = if self.next()? { 0b1 } else { 0b0 };
-            ret |= bit << (bits - i - 1);
-        }
-        Some(ret)
-    }
-
-    fn read2(&mut self, bits: u8) -> Option<u16> {
-        let mut ret = 0;
-        for i in 0..bits {
-            let bit = if self.next()? { 0b1 } else { 0b0 };
-            ret |= bit << (bits - i - 1);
-        }
-        Some(ret)
-    }
-}
 
 type Version = u8;
 
@@ -109,42 +27,49 @@ pub enum Packet {
 }
 
 impl Packet {
-    fn from_iterator(b: &mut BitIterator) -> Result<Self> {
+    fn from_reader(b: &mut BitReader) -> Result<Self> {
         let version = b.read(3).context("Failed to read version")?;
-        let type_id = Type::try_from(b.read(3).context("Failed to read type_id")?)
+        let type_id = Type::try_from(b.read::<u8>(3).context("Failed to read type_id")?)
             .context("Invalid type_id")?;
         if type_id == Type::Literal {
             let mut prefix = true;
             let mut data = 0;
             while prefix {
-                prefix = b.next().context("Failed to read prefix")?;
+                prefix = b.read_bit().context("Failed to read prefix")?;
                 data <<= 4;
-                data |= b.read(4).context("Failed to read prefix")? as u64
+                data |= b.read::<u64>(4).context("Failed to read prefix")?
             }
             Ok(Packet::Lit(version, data))
         } else {
-            let length_type_id: bool = b.next().context("Failed to read length_type_id")?;
+            let length_type_id: bool = b.read_bit().context("Failed to read length_type_id")?;
             let mut sub_packets: Vec<Packet> = Vec::new();
             if !length_type_id {
                 let subpackets_length_bits = b
-                    .read2(15)
+                    .read(15)
                     .context("Failed to read subpackets_length_bits")?;
-                let start = b.num_remaining;
-                while start - b.num_remaining < (subpackets_length_bits as usize) {
-                    sub_packets.push(Packet::from_iterator(b)?);
+                let mut sub_reader = b
+                    .sub_reader(subpackets_length_bits)
+                    .context("Subpackets overrun the packet")?;
+                while !sub_reader.is_empty() {
+                    sub_packets.push(Packet::from_reader(&mut sub_reader)?);
                 }
             } else {
-                let num_sub_packets = b
-                    .read2(11)
+                let num_sub_packets: u16 = b
+                    .read(11)
                     .context("Failed to read additional_sub_packets")?;
                 for _ in 0..num_sub_packets {
-                    sub_packets.push(Packet::from_iterator(b)?);
+                    sub_packets.push(Packet::from_reader(b)?);
                 }
             }
             Ok(Packet::Op(version, type_id, sub_packets))
         }
     }
 
+    fn from_hex(input: &str) -> Result<Self> {
+        let bytes = hex_to_bytes(input.trim()).context("failed to parse hex")?;
+        Packet::from_reader(&mut BitReader::new(&bytes)).context("failed to parse packet")
+    }
+
     fn get_version_numbers(&self) -> Vec<u8> {
         match self {
             Packet::Op(version, _type_i, sub_packets) => {
@@ -198,10 +123,7 @@ impl ParseInput<'_, { Day::Day16 }> for AOC2021<{ Day::Day16 }> {
     type Parsed = Packet;
 
     fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
-        Ok(
-            Packet::from_iterator(&mut BitIterator::from_str(input).expect("failed to parse hex"))
-                .expect("failed to parse packet"),
-        )
+        Packet::from_hex(input)
     }
 }
 
@@ -229,89 +151,34 @@ mod tests {
     use aoc_runner::PartOneVerifier;
 
     #[test]
-    fn test_hex_parsing() {
-        assert_eq!(hex_to_array("2F14DF"), Ok(("", vec![47, 20, 223])));
-    }
-
-    #[test]
-    fn test_from_hex() {
-        assert_eq!(from_hex("0"), Ok(0));
-        assert_eq!(from_hex("1"), Ok(1));
-        assert_eq!(from_hex("9"), Ok(9));
-        assert_eq!(from_hex("a"), Ok(10));
-        assert_eq!(from_hex("f"), Ok(15));
-        assert_eq!(from_hex("A"), Ok(10));
-        assert_eq!(from_hex("F"), Ok(15));
-    }
-
-    #[test]
-    fn test_hex_color() {
-        assert_eq!(hex2("2f"), Ok(("", 47)));
-        assert_eq!(hex2("14"), Ok(("", 20)));
-        assert_eq!(hex2("DF"), Ok(("", 223)));
-        assert_eq!(hex2("28"), Ok(("", 40)));
-    }
-
-    #[test]
-    fn test_hex_binary0() {
-        assert_eq!(format!("{:08b}", 40), "00101000");
-
-        let (rem, output) = hex2("05").unwrap();
-        assert!(rem.is_empty());
-        assert_eq!(format!("{:08b}", output), "00000101");
-
-        let (rem, val) = hex_to_array("D2FE28").expect("is ok");
-        assert!(rem.is_empty());
-        assert_eq!(
-            val.iter().map(|v| format!("{:08b}", v)).collect::<String>(),
-            "110100101111111000101000"
-        );
-    }
-
-    #[test]
-    fn test_hex_binary1() {
-        let (rem, val) = hex_to_array("D2FE28").expect("is ok");
-        assert!(rem.is_empty());
+    fn test_hex_binary() {
+        let binary = |hex| {
+            hex_to_bytes(hex)
+                .expect("is ok")
+                .iter()
+                .map(|v| format!("{:08b}", v))
+                .collect::<String>()
+        };
+        assert_eq!(binary("D2FE28"), "110100101111111000101000");
         assert_eq!(
-            val.iter().map(|v| format!("{:08b}", v)).collect::<String>(),
-            "110100101111111000101000"
-        );
-    }
-
-    #[test]
-    fn test_hex_binary2() {
-        let (rem, val) = hex_to_array("38006F45291200").expect("is ok");
-        assert!(rem.is_empty());
-        assert_eq!(
-            val.iter().map(|v| format!("{:08b}", v)).collect::<String>(),
+            binary("38006F45291200"),
             "00111000000000000110111101000101001010010001001000000000"
         );
-    }
-
-    #[test]
-    fn test_hex_binary3() {
-        let (rem, val) = hex_to_array("EE00D40C823060").expect("is ok");
-        assert!(rem.is_empty());
         assert_eq!(
-            val.iter().map(|v| format!("{:08b}", v)).collect::<String>(),
+            binary("EE00D40C823060"),
             "11101110000000001101010000001100100000100011000001100000"
         );
     }
 
     #[test]
     fn test_literal_packet() {
-        let packet =
-            Packet::from_iterator(&mut BitIterator::from_str("D2FE28").expect("bit iterator"))
-                .expect("is a packet");
+        let packet = Packet::from_hex("D2FE28").expect("is a packet");
         assert_eq!(packet, Packet::Lit(6, 2021));
     }
 
     #[test]
     fn test_operator_packet() {
-        let packet = Packet::from_iterator(
-            &mut BitIterator::from_str("38006F45291200").expect("bit iterator"),
-        )
-        .expect("is a packet");
+        let packet = Packet::from_hex("38006F45291200").expect("is a packet");
         let expected_sub_packets = vec![Packet::Lit(6, 10), Packet::Lit(2, 20)];
         let expected = Packet::Op(1, Type::LessThan, expected_sub_packets);
         assert_eq!(packet, expected);
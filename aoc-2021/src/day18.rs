@@ -1,185 +1,263 @@
 use super::AOC2021;
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use aoc_runner::tree::{NodeId, Tree};
 use aoc_runner::{Day, ParseInput, Part, Solution};
+use std::fmt::Display;
+use std::str::FromStr;
 
-use nom::{
-  IResult,
-  sequence::{separated_pair, delimited}};
-use nom::character::complete::{char, digit1};
-use nom::branch::alt;
-use nom::combinator::{map_res, map};
-use core::str::FromStr;
-use std::{fmt::Display, iter::Sum, cell::RefCell, rc::Rc, borrow::Borrow};
-
-#[derive(Eq, PartialEq, Debug, Clone)]
-pub enum SnailFishNumber {
-    Pair(Rc<RefCell<SnailFishNumber>>, Rc<RefCell<SnailFishNumber>>),
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Element {
+    Pair,
     Reg(u32),
 }
 
+/// A snailfish number as a binary tree whose pairs have exactly two children.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnailFishNumber(Tree<Element>);
+
+impl FromStr for SnailFishNumber {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut tree: Option<Tree<Element>> = None;
+        let mut open: Vec<NodeId> = Vec::new();
+        let mut chars = s.trim().chars().peekable();
+        while let Some(c) = chars.next() {
+            let element = match c {
+                '[' => Element::Pair,
+                ',' => continue,
+                ']' => {
+                    open.pop().context("unbalanced ]")?;
+                    continue;
+                }
+                _ => {
+                    let mut digits = c.to_string();
+                    while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(d);
+                    }
+                    Element::Reg(digits.parse()?)
+                }
+            };
+            let id = match (&mut tree, open.last()) {
+                (None, _) => {
+                    let new = Tree::new(element);
+                    let root = new.root();
+                    tree = Some(new);
+                    root
+                }
+                (Some(tree), Some(&parent)) => tree.add_child(parent, element),
+                (Some(_), None) => return Err(anyhow!("trailing input after number")),
+            };
+            if element == Element::Pair {
+                open.push(id);
+            }
+        }
+        if !open.is_empty() {
+            return Err(anyhow!("unclosed ["));
+        }
+        tree.map(SnailFishNumber).context("empty snailfish number")
+    }
+}
+
 impl Display for SnailFishNumber {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SnailFishNumber::Pair(l, r) => {
-                let l: &RefCell<SnailFishNumber> = l.borrow();
-                let r: &RefCell<SnailFishNumber> = r.borrow();
-                write!(f, "[{},{}]", l.borrow(), r.borrow())
-            },
-            SnailFishNumber::Reg(num) => write!(f, "{}", num),
-        }
+        let tree = &self.0;
+        let text = tree.fold(tree.root(), |element, children: &[String]| match element {
+            Element::Pair => format!("[{},{}]", children[0], children[1]),
+            Element::Reg(num) => num.to_string(),
+        });
+        write!(f, "{text}")
     }
 }
 
-type Inorder = Vec<(Rc<RefCell<SnailFishNumber>>, u32)>;
 impl SnailFishNumber {
-    fn inorder_traversal_helper(snailfish_num: &Rc<RefCell<Self>>, depth: u32, inorder: &mut Inorder) {
-        match *<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(snailfish_num).borrow() {
-            SnailFishNumber::Pair(l, r) => {
-                Self::inorder_traversal_helper(&l, depth + 1, inorder);
-                inorder.push((Rc::clone(&snailfish_num), depth));
-                Self::inorder_traversal_helper(&r, depth + 1, inorder);
-            }
-            SnailFishNumber::Reg(_) => {
-                inorder.push((Rc::clone(&snailfish_num), depth));
-            }
+    fn regular(&self, id: NodeId) -> Option<u32> {
+        match self.0[id] {
+            Element::Reg(num) => Some(num),
+            Element::Pair => None,
         }
     }
 
-    fn inorder_traversal(snailfish_num: &Rc<RefCell<Self>>) -> Inorder {
-        let mut ret = Vec::new();
-        Self::inorder_traversal_helper(snailfish_num ,0, &mut ret);
-        ret
-    }
-
-    // To reduce a snailfish number, you must repeatedly do the first action in this list that applies to the snailfish number:
-    // If any pair is nested inside four pairs, the leftmost such pair explodes.
-    // If any regular number is 10 or greater, the leftmost such regular number splits.
-    fn number_reduce(snailfish_num: &Rc<RefCell<Self>>) -> bool {
-        let mut inorder = Self::inorder_traversal(snailfish_num);
-        for (i, (num, depth)) in inorder.iter().enumerate() {
-            match (num, depth >= &4) {
-                (p, true) => 
-                    if let SnailFishNumber::Pair(l, r) = &*p.borrow_mut() {
-                        if matches!(*<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(snailfish_num).borrow(), SnailFishNumber::Reg(_)) && matches!(*<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(snailfish_num).borrow(), SnailFishNumber::Reg(_)) {
-                            p.borrow_mut().explode(&mut inorder, i);
-                            return true;
-                        }
-                    },
-                _ => (),
-            }
+    // If any pair is nested inside four pairs, the leftmost such pair explodes: its left value
+    // is added to the first regular number to its left (if any), its right value to the first
+    // regular number to its right (if any), and the pair becomes the regular number 0.
+    fn explode(&mut self) -> bool {
+        let tree = &self.0;
+        let leaves: Vec<NodeId> = tree
+            .in_order(tree.root())
+            .into_iter()
+            .filter(|&id| tree.is_leaf(id))
+            .collect();
+        let Some(index) = leaves
+            .iter()
+            .position(|&leaf| tree.depth(leaf) > 4 && tree.next_sibling(leaf).is_some())
+        else {
+            return false;
+        };
+        let pair = tree.parent(leaves[index]).unwrap();
+        let (left, right) = (leaves[index], leaves[index + 1]);
+        let (left_value, right_value) = (self.regular(left).unwrap(), self.regular(right).unwrap());
+        if let Some(&neighbour) = index.checked_sub(1).and_then(|i| leaves.get(i)) {
+            self.0[neighbour] = Element::Reg(self.regular(neighbour).unwrap() + left_value);
         }
-        for (num, _) in inorder.iter() {
-            if let SnailFishNumber::Reg(val) = &*num.borrow_mut() {
-                if val >= &10 {
-                    *num.borrow_mut() = SnailFishNumber::Pair(
-                        Rc::new(RefCell::new(SnailFishNumber::Reg(val / 2))),
-                        Rc::new(RefCell::new(SnailFishNumber::Reg(val / 2 + val % 2)))
-                        );
-                }
-            }
+        if let Some(&neighbour) = leaves.get(index + 2) {
+            self.0[neighbour] = Element::Reg(self.regular(neighbour).unwrap() + right_value);
         }
-
-        false
+        self.0.replace_subtree(pair, Element::Reg(0));
+        true
     }
 
-    //To explode a pair, the pair's left value is added to the first regular number to the left of the exploding pair (if any), and the pair's right value is added to the first regular number to the right of the exploding pair (if any). Exploding pairs will always consist of two regular numbers. Then, the entire exploding pair is replaced with the regular number 0.
-    fn explode(&mut self, inorder: &mut Inorder, index: usize) {
-        let (l, r) = match &*self {
-            SnailFishNumber::Pair(l, r) => {
-                if let SnailFishNumber::Reg(l) = *l.borrow_mut() {
-                    if let SnailFishNumber::Reg(r) = *r.borrow_mut() {
-                        (l, r)
-                    } else {
-                    panic!("cannot explode pair containing non regular numbers");
-                    }
-                } else {
-                    panic!("cannot explode pair containing non regular numbers");
-                }
-            }
-            _ => panic!("cannot explode reg number"),
+    // If any regular number is 10 or greater, the leftmost such regular number splits into a
+    // pair of it halved, rounding down on the left and up on the right.
+    fn split(&mut self) -> bool {
+        let tree = &self.0;
+        let Some((id, value)) = tree
+            .in_order(tree.root())
+            .into_iter()
+            .filter_map(|id| Some((id, self.regular(id)?)))
+            .find(|&(_, value)| value >= 10)
+        else {
+            return false;
         };
+        self.0.replace(id, Element::Pair);
+        self.0.add_child(id, Element::Reg(value / 2));
+        self.0.add_child(id, Element::Reg(value - value / 2));
+        true
+    }
 
-        for i in (index - 1)..0 {
-            let (num, _depth) = &mut inorder[i];
-            if let SnailFishNumber::Reg(val) = *<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(num).borrow() {
-                *num = Rc::new(RefCell::new(SnailFishNumber::Reg(val + l)));
-            }
-        }
+    fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
 
-        for i in (index + 1)..inorder.len() {
-            let (num, _depth) = &mut inorder[i];
-            if let SnailFishNumber::Reg(val) = *<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(num).borrow() {
-                *num = Rc::new(RefCell::new(SnailFishNumber::Reg(val + r)));
-            }
-        }
+    fn add(&self, other: &Self) -> Self {
+        let mut tree = Tree::new(Element::Pair);
+        let root = tree.root();
+        tree.graft(root, &self.0, self.0.root());
+        tree.graft(root, &other.0, other.0.root());
+        let mut sum = SnailFishNumber(tree);
+        sum.reduce();
+        sum
     }
 
     fn magnitude(&self) -> u32 {
-        match self {
-            SnailFishNumber::Pair(l, r) => 3 * (*<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(l).borrow()).magnitude() + 2 * (*<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(l).borrow()).magnitude(),
-            SnailFishNumber::Reg(num) => *num,
-        }
+        let tree = &self.0;
+        tree.fold(tree.root(), |element, children: &[u32]| match element {
+            Element::Pair => 3 * children[0] + 2 * children[1],
+            Element::Reg(num) => *num,
+        })
     }
 }
 
+impl ParseInput<'_, { Day::Day18 }> for AOC2021<{ Day::Day18 }> {
+    type Parsed = Vec<SnailFishNumber>;
 
-fn add(l: Rc<RefCell<SnailFishNumber>>, r: Rc<RefCell<SnailFishNumber>>) -> Rc<RefCell<SnailFishNumber>> {
-    let ret = Rc::new(RefCell::new(SnailFishNumber::Pair(l, r)));
-    while SnailFishNumber::number_reduce(&ret) {}
-    ret
+    fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
+        input
+            .trim()
+            .lines()
+            .map(SnailFishNumber::from_str)
+            .collect()
+    }
 }
 
-impl Sum<Rc<RefCell<SnailFishNumber>>> for SnailFishNumber {
-    fn sum<I: Iterator<Item = Rc<RefCell<SnailFishNumber>>>>(mut iter: I) -> Self {
-        let ret = iter.next();
-        if ret == None { return SnailFishNumber::Reg(0) }
-        let mut ret = ret.unwrap();
+impl Solution<'_, { Day::Day18 }, { Part::One }> for AOC2021<{ Day::Day18 }> {
+    type Input = Vec<SnailFishNumber>;
+    type Output = u32;
 
-        loop {
-            match iter.next() {
-                Some(other) => ret = add(ret, other),
-                None => return (*<Rc<RefCell<SnailFishNumber>> as Borrow<RefCell<SnailFishNumber>>>::borrow(&ret).borrow()).clone(),
-            };
-        }
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        let (first, rest) = input.split_first().context("no snailfish numbers")?;
+        let sum = rest.iter().fold(first.clone(), |sum, num| sum.add(num));
+        Ok(sum.magnitude())
     }
 }
 
-fn map_from_str<T: FromStr>(input: &str) -> IResult<&str, T> {
-    map_res(digit1, FromStr::from_str)(input)
-}
+impl Solution<'_, { Day::Day18 }, { Part::Two }> for AOC2021<{ Day::Day18 }> {
+    type Input = Vec<SnailFishNumber>;
+    type Output = u32;
 
-fn pair(input: &str) -> IResult<&str, SnailFishNumber> {
-    delimited(
-        char('['),
-        map(separated_pair(snail_number, char(','), snail_number),
-        |(l,r)| SnailFishNumber::Pair(Rc::new(RefCell::new(l)), Rc::new(RefCell::new(r)))),
-        char(']'),
-    )(input)
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        input
+            .iter()
+            .enumerate()
+            .flat_map(|(i, l)| {
+                input
+                    .iter()
+                    .enumerate()
+                    .filter(move |(j, _)| i != *j)
+                    .map(move |(_, r)| l.add(r).magnitude())
+            })
+            .max()
+            .context("need at least two snailfish numbers")
+    }
 }
 
-fn value(input: &str) -> IResult<&str, SnailFishNumber> {
-    map(map_from_str, SnailFishNumber::Reg)(input)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_runner::roundtrip;
+    use aoc_runner::testing::Rng;
+    use aoc_runner::PartOneVerifier;
+    use aoc_runner::PartTwoVerifier;
 
-fn snail_number(input: &str) -> IResult<&str, SnailFishNumber> {
-    alt((pair, value))(input)
-}
+    fn arbitrary_number(rng: &mut Rng, size: usize) -> SnailFishNumber {
+        fn add(tree: &mut Tree<Element>, parent: NodeId, rng: &mut Rng, depth: usize) {
+            for _ in 0..2 {
+                if depth > 0 && rng.bool() {
+                    let pair = tree.add_child(parent, Element::Pair);
+                    add(tree, pair, rng, depth - 1);
+                } else {
+                    tree.add_child(parent, Element::Reg(rng.below(20) as u32));
+                }
+            }
+        }
+        let mut tree = Tree::new(Element::Pair);
+        let root = tree.root();
+        add(&mut tree, root, rng, size.min(6));
+        SnailFishNumber(tree)
+    }
 
-impl ParseInput<'_, { Day::Day18 }> for AOC2021<{ Day::Day18 }> {
-    type Parsed = Vec<Rc<RefCell<SnailFishNumber>>>;
+    roundtrip!(test_roundtrip, arbitrary_number, |s| s.parse().ok());
 
-    fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
-        input.lines().map(|l| snail_number(l).context("failed to parse line")?).map(|(_, v)| Rc::new(RefCell::new(v))).collect()
+    fn reduced(input: &str) -> String {
+        let mut num = SnailFishNumber::from_str(input).expect("valid number");
+        num.reduce();
+        num.to_string()
     }
-}
 
-impl Solution<'_, { Day::Day18 }, { Part::One }> for AOC2021<{ Day::Day18 }> {
-    type Input = Vec<Rc<RefCell<SnailFishNumber>>>;
-    type Output = u32;
+    #[test]
+    fn test_reduce() {
+        assert_eq!(reduced("[[[[[9,8],1],2],3],4]"), "[[[[0,9],2],3],4]");
+        assert_eq!(reduced("[7,[6,[5,[4,[3,2]]]]]"), "[7,[6,[5,[7,0]]]]");
+        assert_eq!(
+            reduced("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]"),
+            "[[3,[2,[8,0]]],[9,[5,[7,0]]]]"
+        );
+        assert_eq!(
+            reduced("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]"),
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+        );
+        assert_eq!(
+            SnailFishNumber::from_str("[[1,2],[[3,4],5]]")
+                .unwrap()
+                .magnitude(),
+            143
+        );
+    }
 
-    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let sum: SnailFishNumber = input.clone().into_iter().sum();
-        sum.magnitude()
+    #[test]
+    fn test() -> Result<()> {
+        let input = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+        let problem = super::AOC2021::<{ Day::Day18 }>;
+        problem.test_part1(input, 4140)?;
+        problem.test_part2(input, 3993)
     }
 }
-
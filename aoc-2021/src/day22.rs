@@ -1 +1,89 @@
-pub fn solve(input_path: &str) {}
+use super::AOC2021;
+use anyhow::Result;
+use aoc_runner::cuboid::{Cuboid, CuboidSet};
+use aoc_runner::parse::{lines_of, parse_all, signed};
+use aoc_runner::point3d::Point3D;
+use aoc_runner::{Day, ParseInput, Part, Solution};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, one_of};
+use nom::combinator::value;
+use nom::sequence::{preceded, separated_pair, tuple};
+use nom::IResult;
+
+pub struct Step {
+    on: bool,
+    cuboid: Cuboid,
+}
+
+fn range(input: &str) -> IResult<&str, (i64, i64)> {
+    preceded(
+        tuple((one_of("xyz"), char('='))),
+        separated_pair(signed, tag(".."), signed),
+    )(input)
+}
+
+fn step(input: &str) -> IResult<&str, Step> {
+    let (input, on) = alt((value(true, tag("on ")), value(false, tag("off "))))(input)?;
+    let (input, (x, _, y, _, z)) = tuple((range, char(','), range, char(','), range))(input)?;
+    let cuboid = Cuboid::new(Point3D::new(x.0, y.0, z.0), Point3D::new(x.1, y.1, z.1));
+    Ok((input, Step { on, cuboid }))
+}
+
+fn reboot<'a>(steps: impl IntoIterator<Item = &'a Step>) -> CuboidSet {
+    let mut cubes = CuboidSet::new();
+    for step in steps {
+        if step.on {
+            cubes.insert(step.cuboid);
+        } else {
+            cubes.remove(&step.cuboid);
+        }
+    }
+    cubes
+}
+
+impl ParseInput<'_, { Day::Day22 }> for AOC2021<{ Day::Day22 }> {
+    type Parsed = Vec<Step>;
+
+    fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
+        parse_all(lines_of(step), input)
+    }
+}
+
+impl Solution<'_, { Day::Day22 }, { Part::One }> for AOC2021<{ Day::Day22 }> {
+    type Input = Vec<Step>;
+    type Output = i64;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        let region = Cuboid::new(Point3D::new(-50, -50, -50), Point3D::new(50, 50, 50));
+        let cubes = reboot(input.iter().filter(|step| step.cuboid.intersects(&region)));
+        Ok(cubes.volume_within(&region))
+    }
+}
+
+impl Solution<'_, { Day::Day22 }, { Part::Two }> for AOC2021<{ Day::Day22 }> {
+    type Input = Vec<Step>;
+    type Output = i64;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        Ok(reboot(input).volume())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_runner::PartOneVerifier;
+    use aoc_runner::PartTwoVerifier;
+
+    #[test]
+    fn test() -> Result<()> {
+        let small = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+        let problem = super::AOC2021::<{ Day::Day22 }>;
+        problem.test_part1(small, 39)?;
+        problem.test_part2(small, 39)
+    }
+}
@@ -20,19 +20,21 @@ mod day14;
 mod day15;
 mod day16;
 mod day17;
+mod day18;
+mod day22;
 
 use anyhow::anyhow;
 use anyhow::Result;
+use aoc_runner::input::read_input;
 use aoc_runner::run_solutions;
 use aoc_runner::Day;
 use aoc_runner::SolutionRunner;
-use std::fs::read_to_string;
 
 pub struct AOC2021<const DAY: Day>;
 
 fn input(day: &Day) -> Result<String> {
     let day: u8 = (*day).into();
-    match read_to_string(format!("aoc-2021/input/day{day}.txt")) {
+    match read_input(format!("aoc-2021/input/day{day}.txt")) {
         Ok(s) => Ok(s),
         Err(e) => Err(e.into()),
     }
@@ -58,6 +60,8 @@ fn solve(day: &Day) -> Result<()> {
         Day::Day15 => AOC2021::<{ Day::Day15 }>.run(&input),
         Day::Day16 => AOC2021::<{ Day::Day16 }>.run(&input),
         Day::Day17 => AOC2021::<{ Day::Day17 }>.run(&input),
+        Day::Day18 => AOC2021::<{ Day::Day18 }>.run(&input),
+        Day::Day22 => AOC2021::<{ Day::Day22 }>.run(&input),
         _ => Err(anyhow!("Day not implemented yet")),
     }
 }
@@ -1,12 +1,10 @@
-use std::cell::RefCell;
 use std::fmt::{self, Debug};
-use std::rc::Rc;
 
 use super::AOC2022;
 use anyhow::{Context, Result};
+use aoc_runner::tree::{NodeId, Tree};
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use camino::Utf8PathBuf;
-use indexmap::IndexMap;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while};
 use nom::character::complete::{newline, space0, space1};
@@ -76,23 +74,38 @@ fn parse_lines(s: &str) -> IResult<&str, Vec<TerminalOutput>> {
     separated_list0(newline, parse_line)(s)
 }
 
-type NodeHandle = Rc<RefCell<Node>>;
-
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Node {
+    name: Utf8PathBuf,
     size: u64,
-    children: IndexMap<Utf8PathBuf, NodeHandle>,
-    parent: Option<NodeHandle>,
+}
+
+type FileSystem = Tree<Node>;
+
+/// The child of `dir` called `name`, added as an empty entry if it hasn't been seen yet.
+fn child_named(fs: &mut FileSystem, dir: NodeId, name: Utf8PathBuf) -> NodeId {
+    match fs
+        .children(dir)
+        .iter()
+        .find(|&&child| fs[child].name == name)
+    {
+        Some(&child) => child,
+        None => fs.add_child(dir, Node { name, size: 0 }),
+    }
 }
 
 impl ParseInput<'_, { Day::Day7 }> for AOC2022<{ Day::Day7 }> {
-    type Parsed = NodeHandle;
+    type Parsed = FileSystem;
 
     fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
         let (_, terminal_output) = parse_lines(input).map_err(|e| e.to_owned())?;
 
-        let root = Rc::new(RefCell::new(Node::default()));
-        let mut curr = root.clone();
+        let mut fs = Tree::new(Node {
+            name: "/".into(),
+            size: 0,
+        });
+        let root = fs.root();
+        let mut curr = root;
 
         for line in terminal_output {
             match line {
@@ -100,113 +113,90 @@ impl ParseInput<'_, { Day::Day7 }> for AOC2022<{ Day::Day7 }> {
                     Command::LS => {}
                     Command::CD => match path.as_str() {
                         "/" => {
-                            curr = root.clone();
+                            curr = root;
                         }
                         ".." => {
-                            let node = curr.borrow().parent.clone().unwrap();
-                            curr = node;
+                            curr = fs.parent(curr).context("cd .. from the root")?;
                         }
                         _ => {
-                            let node = curr.borrow_mut().children.entry(path).or_default().clone();
-                            curr = node;
+                            curr = child_named(&mut fs, curr, path);
                         }
                     },
                 },
                 TerminalOutput::Entry(entry) => match entry {
                     Entry::Dir(name) => {
-                        let child = curr.borrow_mut().children.entry(name).or_default().clone();
-                        child.borrow_mut().parent = Some(curr.clone());
+                        child_named(&mut fs, curr, name);
                     }
                     Entry::File(name, size) => {
-                        let child = curr.borrow_mut().children.entry(name).or_default().clone();
-                        child.borrow_mut().size = size;
-                        child.borrow_mut().parent = Some(curr.clone());
+                        let child = child_named(&mut fs, curr, name);
+                        fs[child].size = size;
                     }
                 },
             }
         }
-        Ok(root)
+        Ok(fs)
     }
 }
 
-impl Node {
-    fn is_dir(&self) -> bool {
-        self.size == 0 && !self.children.is_empty()
-    }
+fn is_dir(fs: &FileSystem, node: NodeId) -> bool {
+    fs[node].size == 0 && !fs.is_leaf(node)
+}
 
-    fn total_size(&self) -> u64 {
-        self.size
-            + self
-                .children
-                .values()
-                .map(|c| c.borrow().total_size())
-                .sum::<u64>()
-    }
+/// The total size of every directory.
+fn dir_sizes(fs: &FileSystem) -> Vec<u64> {
+    let totals = fs.fold_all(fs.root(), |node, children: &[u64]| {
+        node.size + children.iter().sum::<u64>()
+    });
+    fs.pre_order(fs.root())
+        .into_iter()
+        .filter(|&node| is_dir(fs, node))
+        .map(|node| totals[&node])
+        .collect()
 }
 
-struct PrettyNode<'a>(&'a NodeHandle, Utf8PathBuf, u64);
+struct PrettyNode<'a>(&'a FileSystem, NodeId, u64);
 
 impl<'a> fmt::Debug for PrettyNode<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let this = self.0.borrow();
+        let PrettyNode(fs, id, depth) = *self;
+        let this = &fs[id];
 
-        let depth = "  ".repeat(self.2 as usize);
+        let indent = "  ".repeat(depth as usize);
         if this.size == 0 {
-            writeln!(f, "{}- {} (dir)", depth, self.1)?;
+            writeln!(f, "{}- {} (dir)", indent, this.name)?;
         } else {
-            writeln!(f, "{}- {} (file, size={})", depth, self.1, this.size)?;
+            writeln!(f, "{}- {} (file, size={})", indent, this.name, this.size)?;
         }
 
-        for (name, child) in &this.children {
-            write!(f, "{:?}", PrettyNode(child, name.clone(), self.2 + 1))?;
+        for &child in fs.children(id) {
+            write!(f, "{:?}", PrettyNode(fs, child, depth + 1))?;
         }
         Ok(())
     }
 }
 
-fn all_dirs(node: NodeHandle) -> Box<dyn Iterator<Item = NodeHandle>> {
-    #[allow(clippy::needless_collect)]
-    let children = node.borrow().children.values().cloned().collect::<Vec<_>>();
-    Box::new(
-        std::iter::once(node).chain(
-            children
-                .into_iter()
-                .filter_map(|child| {
-                    if child.borrow().is_dir() {
-                        Some(all_dirs(child))
-                    } else {
-                        None
-                    }
-                })
-                .flatten(),
-        ),
-    )
-}
-
 impl Solution<'_, { Day::Day7 }, { Part::One }> for AOC2022<{ Day::Day7 }> {
-    type Input = NodeHandle;
+    type Input = FileSystem;
     type Output = u64;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        Ok(all_dirs(input.clone())
-            .map(|d| d.borrow().total_size())
-            .filter(|&s| s < 100_000)
-            .sum())
+        Ok(dir_sizes(input).into_iter().filter(|&s| s < 100_000).sum())
     }
 }
 
 impl Solution<'_, { Day::Day7 }, { Part::Two }> for AOC2022<{ Day::Day7 }> {
-    type Input = NodeHandle;
+    type Input = FileSystem;
     type Output = u64;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
         let max_space_possible = 70000000;
         let needed_free_space = 30000000;
-        let curr_space_used = input.clone().borrow().total_size();
+        let sizes = dir_sizes(input);
+        let curr_space_used = sizes.first().copied().unwrap_or(0);
         let curr_free = max_space_possible - curr_space_used;
         let need_to_delete = needed_free_space - curr_free;
-        all_dirs(input.clone())
-            .map(|d| d.borrow().total_size())
+        sizes
+            .into_iter()
             .filter(|&s| s >= need_to_delete)
             .min()
             .context("No possible directories to delete")
@@ -318,13 +308,8 @@ $ ls
     - k (file, size=7214296)
 ";
         let problem = super::AOC2022::<{ Day::Day7 }>;
-        assert_eq!(
-            format!(
-                "{:?}",
-                PrettyNode(&problem.parse_input(input)?, "/".into(), 0)
-            ),
-            expected
-        );
+        let fs = problem.parse_input(input)?;
+        assert_eq!(format!("{:?}", PrettyNode(&fs, fs.root(), 0)), expected);
         problem.test_part1(input, 95437)
     }
 }
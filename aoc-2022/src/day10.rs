@@ -1,6 +1,5 @@
-use std::collections::HashMap;
-
 use super::AOC2022;
+use aoc_runner::vm::{self, Control, Machine, State, Word};
 use aoc_runner::{Day, ParseInput, Part, Solution};
 
 use anyhow::Result;
@@ -41,74 +40,65 @@ impl ParseInput<'_, { Day::Day10 }> for AOC2022<{ Day::Day10 }> {
         Ok(instructions)
     }
 }
+
+impl vm::Instruction for Instruction {
+    fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+
+    fn execute(&self, state: &mut State) -> Control {
+        if let Instruction::Addx(operand) = self {
+            state.update("x", |x| x + *operand as Word);
+        }
+        Control::Next
+    }
+}
+
+/// Runs the program, calling `hook` with each cycle number and the `X` register during it.
+fn run(input: &[Instruction], mut hook: impl FnMut(usize, Word)) {
+    Machine::new(input.to_vec())
+        .with_register("x", 1)
+        .run_with(|cycle, state| hook(cycle, state.get("x")));
+}
+
 impl Solution<'_, { Day::Day10 }, { Part::One }> for AOC2022<{ Day::Day10 }> {
     type Input = Vec<Instruction>;
     type Output = i32;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let mut cycle = 1;
-        let mut register = 1;
-        let mut signal_strengths = Vec::new();
-        for instruction in input {
-            if cycle == 20 || (cycle - 20) % 40 == 0 {
-                signal_strengths.push(register * cycle);
+        let mut signal_strength = 0;
+        run(input, |cycle, x| {
+            if cycle % 40 == 20 {
+                signal_strength += cycle as Word * x;
             }
-            cycle += 1;
-
-            if let Instruction::Addx(operand) = instruction {
-                if cycle == 20 || (cycle - 20) % 40 == 0 {
-                    signal_strengths.push(register * cycle);
-                }
-                cycle += 1;
-                register += operand;
-            }
-        }
-        Ok(signal_strengths.iter().sum())
+        });
+        Ok(signal_strength.try_into()?)
     }
 }
 
-fn should_light_pixel(cycle: usize, register: i32) -> bool {
-    let sprite_center = (cycle - 1) % 40;
-    return register + 1 == sprite_center as i32
-        || register - 1 == sprite_center as i32
-        || register == sprite_center as i32;
-}
-
 impl Solution<'_, { Day::Day10 }, { Part::Two }> for AOC2022<{ Day::Day10 }> {
     type Input = Vec<Instruction>;
     type Output = String;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let mut crt = HashMap::new();
-        let mut cycle: usize = 1;
-        let mut register: i32 = 1;
-        for instruction in input {
-            crt.insert(
-                (cycle / 40, cycle % 40),
-                should_light_pixel(cycle, register),
-            );
-            cycle += 1;
-
-            if let Instruction::Addx(operand) = instruction {
-                crt.insert(
-                    (cycle / 40, cycle % 40),
-                    should_light_pixel(cycle, register),
-                );
-                cycle += 1;
-                register += operand;
-            }
-        }
         let mut ret = String::from("\n");
-        for row in 0..6 {
-            for col in 1..41 {
-                if *crt.get(&(row, col)).unwrap_or(&false) {
-                    ret.push('#');
-                } else {
-                    ret.push('.');
-                }
+        run(input, |cycle, x| {
+            let column = (cycle - 1) % 40;
+            if cycle > 240 {
+                return;
             }
-            ret.push('\n');
-        }
+            ret.push(if (x - column as Word).abs() <= 1 {
+                '#'
+            } else {
+                '.'
+            });
+            if column == 39 {
+                ret.push('\n');
+            }
+        });
         Ok(ret)
     }
 }
@@ -11,6 +11,7 @@ use nom::{
     IResult,
 };
 use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Packet {
@@ -32,6 +33,24 @@ impl Packet {
     }
 }
 
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Value(n) => write!(f, "{n}"),
+            Packet::List(packets) => {
+                write!(f, "[")?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 fn compare_lists(l: &[Packet], r: &[Packet]) -> Ordering {
     for (a, b) in l.iter().zip(r.iter()) {
         match a.cmp(b) {
@@ -142,8 +161,25 @@ impl Solution<'_, { Day::Day13 }, { Part::Two }> for AOC2022<{ Day::Day13 }> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_runner::parse::parse_all;
+    use aoc_runner::roundtrip;
+    use aoc_runner::testing::Rng;
     use aoc_runner::PartOneVerifier;
 
+    fn arbitrary_packet(rng: &mut Rng, size: usize) -> Packet {
+        if size == 0 || rng.below(3) == 0 {
+            Packet::Value(rng.below(size as u64 + 11) as i32)
+        } else {
+            Packet::List(rng.vec(4, |rng| arbitrary_packet(rng, size / 2)))
+        }
+    }
+
+    roundtrip!(test_roundtrip, arbitrary_packet, |s| parse_all(
+        Packet::parse,
+        s
+    )
+    .ok());
+
     #[test]
     fn test_parse_packet() -> Result<()> {
         assert_eq!(
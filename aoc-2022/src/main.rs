@@ -22,17 +22,17 @@ mod day15;
 use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
+use aoc_runner::input::read_input;
 use aoc_runner::run_solutions;
 use aoc_runner::Day;
 use aoc_runner::SolutionRunner;
-use std::fs::read_to_string;
 
 pub struct AOC2022<const DAY: Day>;
 
 fn input(day: &Day) -> Result<String> {
     let day: u8 = (*day).into();
     let path = format!("aoc-2022/input/day{day}.txt");
-    read_to_string(&path).context(path)
+    read_input(&path).context(path)
 }
 
 fn solve(day: &Day) -> Result<()> {
@@ -1,12 +1,11 @@
 use super::AOC2023;
 use anyhow::Result;
+use aoc_runner::parse::{labelled, numbers};
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, multispace0, newline},
-    combinator::{all_consuming, map, map_res},
-    multi::separated_list0,
-    sequence::{preceded, terminated, tuple},
+    character::complete::newline,
+    combinator::{all_consuming, map},
+    sequence::{terminated, tuple},
     IResult,
 };
 
@@ -27,25 +26,9 @@ impl Race {
     }
 }
 
-fn parse_num(input: &str) -> IResult<&str, Num> {
-    map_res(digit1, |num: &str| Num::from_str_radix(num, 10))(input)
-}
-
-fn time_prefix(input: &str) -> IResult<&str, (&str, &str)> {
-    tuple((tag("Time:"), multispace0))(input)
-}
-
-fn distance_prefix(input: &str) -> IResult<&str, (&str, &str)> {
-    tuple((tag("Distance:"), multispace0))(input)
-}
-
-fn parse_nums(input: &str) -> IResult<&str, Vec<Num>> {
-    separated_list0(multispace0, parse_num)(input)
-}
-
 fn parse_races(input: &str) -> IResult<&str, Vec<Race>> {
-    let parse_times = |input| terminated(preceded(time_prefix, parse_nums), newline)(input);
-    let parse_distances = |input| terminated(preceded(distance_prefix, parse_nums), newline)(input);
+    let parse_times = |input| terminated(labelled("Time", numbers), newline)(input);
+    let parse_distances = |input| terminated(labelled("Distance", numbers), newline)(input);
     map(
         tuple((parse_times, parse_distances)),
         |(times, distances)| {
@@ -1,7 +1,7 @@
 use super::AOC2023;
 use anyhow::{Context, Result};
+use aoc_runner::poly::{next_value, previous_value};
 use aoc_runner::{Day, ParseInput, Part, Solution};
-use itertools::Itertools;
 
 type Num = i64;
 type History = Vec<Num>;
@@ -21,27 +21,11 @@ impl ParseInput<'_, { Day::Day9 }> for AOC2023<{ Day::Day9 }> {
 }
 
 fn predict_next_value(history: &[Num]) -> Result<Num> {
-    let mut prediction: Num = *history
-        .last()
-        .context("Cannot predict with empty history.")?;
-    let mut curr: Vec<Num> = history.iter().map(|num| num.to_owned()).collect_vec();
-    while curr.iter().any(|num| *num != 0) {
-        curr = curr.iter().tuple_windows().map(|(l, r)| r - l).collect();
-        prediction += curr.last().context("extrapolation failed.")?;
-    }
-    Ok(prediction)
+    next_value(history).context("Cannot predict with empty history.")
 }
 
 fn predict_previous_value(history: &[Num]) -> Result<Num> {
-    let mut prediction: Num = *history
-        .first()
-        .context("Cannot predict with empty history.")?;
-    let mut curr: Vec<Num> = history.iter().map(|num| num.to_owned()).collect_vec();
-    while curr.iter().any(|num| *num != 0) {
-        curr = curr.iter().tuple_windows().map(|(l, r)| l - r).collect();
-        prediction += curr.first().context("extrapolation failed.")?;
-    }
-    Ok(prediction)
+    previous_value(history).context("Cannot predict with empty history.")
 }
 
 impl Solution<'_, { Day::Day9 }, { Part::One }> for AOC2023<{ Day::Day9 }> {
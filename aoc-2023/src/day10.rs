@@ -1,8 +1,6 @@
 use super::AOC2023;
 use anyhow::{anyhow, Context, Result};
-use aoc_runner::{point2d::Point2D, Day, ParseInput, Part, Solution};
-use colored::Colorize;
-use std::collections::HashSet;
+use aoc_runner::{point2d::Point2D, polygon::interior_points, Day, ParseInput, Part, Solution};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
 
@@ -138,59 +136,17 @@ impl Solution<'_, { Day::Day10 }, { Part::One }> for AOC2023<{ Day::Day10 }> {
 
 impl Solution<'_, { Day::Day10 }, { Part::Two }> for AOC2023<{ Day::Day10 }> {
     type Input = String;
-    type Output = i32;
+    type Output = i64;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
         let map = parse_map(input)?;
-        let loop_points: HashSet<Coord> = find_path(find_start(&map)?, &map)?.into_iter().collect();
-        let mut enclosed_points: HashSet<Coord> = HashSet::new();
-
-        let mut result = 0;
-        for x in 0..map[0].len() {
-            let mut num_intersections = 0;
-            for y in 0..map.len() {
-                let tile = map[y][x];
-                let x = x as i32;
-                let y = y as i32;
-                let point = Coord { x, y };
-                if loop_points.contains(&point) {
-                    if tile != Tile::F && tile != Tile::L && tile != Tile::Pipe {
-                        num_intersections += 1;
-                    }
-                } else if num_intersections % 2 == 1 {
-                    enclosed_points.insert(point);
-                    result += 1;
-                }
-            }
-        }
-
-        for x in 0..map[0].len() {
-            for y in (0..map.len()).rev() {
-                let tile = match map[y][x] {
-                    Tile::Pipe => "|",
-                    Tile::Hyphen => "-",
-                    Tile::L => "L",
-                    Tile::J => "J",
-                    Tile::Seven => "7",
-                    Tile::F => "F",
-                    Tile::Dot => ".",
-                    Tile::Start => "S",
-                };
-                let x = x as i32;
-                let y = y as i32;
-                let point = Coord { x, y };
-                if enclosed_points.contains(&point) {
-                    print!("{}", tile.bright_red());
-                } else if loop_points.contains(&point) {
-                    print!("{}", tile.bright_green());
-                } else {
-                    print!("{tile}");
-                }
-            }
-            println!();
-        }
-
-        Ok(result)
+        let mut path = find_path(find_start(&map)?, &map)?;
+        path.pop();
+        let vertices: Vec<Point2D<i64>> = path
+            .into_iter()
+            .map(|point| Point2D::new(point.x.into(), point.y.into()))
+            .collect();
+        Ok(interior_points(&vertices))
     }
 }
 
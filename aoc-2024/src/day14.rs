@@ -4,6 +4,7 @@ use super::AOC2024;
 use anyhow::Result;
 use aoc_runner::{
     point2d::{recognize_point2d, Point2D},
+    viz::{self, Colour, Frame},
     Day, ParseInput, Part, Solution,
 };
 use counter::Counter;
@@ -51,19 +52,21 @@ impl Lobby {
         counts.values().product()
     }
 
-    fn print(&self) -> std::fmt::Result {
+    /// The number of robots on each tile, drawn with `y` increasing upwards.
+    fn frame(&self) -> Frame {
         let counts: Counter<Point> = self.robots.iter().map(|r| r.p).collect();
-        for y in (0..self.height).rev() {
+        let mut frame = Frame::new(self.width as usize, self.height as usize);
+        for y in 0..self.height {
             for x in 0..self.width {
-                let c = match counts.get(&Point { x, y }) {
-                    None => String::from("."),
-                    Some(n) => format!("{n}"),
+                let (c, colour) = match counts.get(&Point { x, y }) {
+                    None => ('.', Colour::Default),
+                    Some(&n) => (char::from_digit(n as u32, 10).unwrap_or('+'), Colour::Green),
                 };
-                print!("{c}");
+                let p = Point2D::new(x as usize, (self.height - 1 - y) as usize);
+                frame.set(p, c, colour);
             }
-            println!("");
         }
-        Ok(())
+        frame
     }
 }
 
@@ -149,7 +152,11 @@ fn part_2(mut lobby: Lobby) -> usize {
         lobby.step();
         seconds += 1;
     }
-    lobby.print().unwrap();
+    viz::emit(|| {
+        lobby
+            .frame()
+            .with_caption(format!("After {seconds} seconds"))
+    });
     seconds
 }
 
@@ -1,5 +1,6 @@
 use super::AOC2025;
 use anyhow::Result;
+use aoc_runner::input::Expectation;
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use itertools::Itertools;
 use std::str::FromStr;
@@ -19,6 +20,10 @@ impl ParseInput<'_, { Day::Day2 }> for AOC2025<{ Day::Day2 }> {
             })
             .collect())
     }
+
+    fn expectations(&self) -> &'static [Expectation] {
+        &[Expectation::NonEmpty, Expectation::SingleLine]
+    }
 }
 
 impl Solution<'_, { Day::Day2 }, { Part::One }> for AOC2025<{ Day::Day2 }> {
@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use super::AOC2025;
 use anyhow::Result;
+use aoc_runner::input::Expectation;
 use aoc_runner::{Day, ParseInput, Part, Solution};
 
 type IR = Vec<Vec<char>>;
@@ -17,6 +18,10 @@ impl ParseInput<'_, { Day::Day4 }> for AOC2025<{ Day::Day4 }> {
             .map(|line| line.chars().collect())
             .collect())
     }
+
+    fn expectations(&self) -> &'static [Expectation] {
+        &[Expectation::NonEmpty, Expectation::Rectangular]
+    }
 }
 
 impl Solution<'_, { Day::Day4 }, { Part::One }> for AOC2025<{ Day::Day4 }> {
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use super::AOC2025;
 use anyhow::{Context, Result};
-use aoc_runner::graph::{Edge, Graph, kruskal, kruskal_with_limit};
+use aoc_runner::graph::{Edge, Graph, connecting_edge, kruskal_with_limit};
 use aoc_runner::point3d::{Point3D, euclidean_distance_squared};
 use aoc_runner::{Day, ParseInput, Part, Solution};
 
@@ -86,13 +86,9 @@ impl Solution<'_, { Day::Day8 }, { Part::One }> for AOC2025<{ Day::Day8 }> {
     type Output = usize;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        let mut connected_components = kruskal_with_limit(input, 1000).sets();
-        connected_components.sort_by_key(|set| set.len());
-        let ret = connected_components
+        let ret = kruskal_with_limit(input, 1000)
+            .largest_k_components(3)
             .iter()
-            .rev()
-            .take(3)
-            .map(|set| set.len())
             .product();
 
         Ok(ret)
@@ -104,9 +100,8 @@ impl Solution<'_, { Day::Day8 }, { Part::Two }> for AOC2025<{ Day::Day8 }> {
     type Output = i64;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        Ok(kruskal(input)
-            .last()
+        connecting_edge(input)
             .map(|edge| edge.coord1.x * edge.coord2.x)
-            .context("Empty Kruskal??")?)
+            .context("junction boxes never form a single circuit")
     }
 }
@@ -5,6 +5,7 @@ use std::str::FromStr;
 use super::AOC2025;
 use anyhow::Result;
 use aoc_runner::point2d::{Point2D, manhattan_area_inclusive};
+use aoc_runner::polygon::contains_rect;
 use aoc_runner::{Day, ParseInput, Part, Solution};
 
 type IR = Vec<Point2D<i64>>;
@@ -35,3 +36,18 @@ impl Solution<'_, { Day::Day9 }, { Part::One }> for AOC2025<{ Day::Day9 }> {
             .context("nonempty input")
     }
 }
+
+impl Solution<'_, { Day::Day9 }, { Part::Two }> for AOC2025<{ Day::Day9 }> {
+    type Input = IR;
+    type Output = i64;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        input
+            .iter()
+            .combinations(2)
+            .filter(|pair| contains_rect(input, *pair[0], *pair[1]))
+            .map(|pair| manhattan_area_inclusive(pair[0], pair[1]))
+            .max()
+            .context("nonempty input")
+    }
+}
@@ -2,7 +2,8 @@ use rayon::prelude::*;
 use std::collections::VecDeque;
 
 use super::AOC2025;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use aoc_runner::linear::minimize_sum;
 use aoc_runner::{Day, ParseInput, Part, Solution};
 use nom::{
     IResult,
@@ -43,6 +44,20 @@ impl Machine {
             }
         }
     }
+
+    pub fn num_joltage_presses(&self) -> Option<u64> {
+        let a: Vec<Vec<i64>> = (0..self.joltage.len())
+            .map(|counter| {
+                self.wiring
+                    .iter()
+                    .map(|schematic| schematic.contains(&counter) as i64)
+                    .collect()
+            })
+            .collect();
+        let b: Vec<i64> = self.joltage.iter().map(|&j| j as i64).collect();
+        let presses = minimize_sum(&a, &b)?;
+        Some(presses.iter().sum::<i64>() as u64)
+    }
 }
 
 fn parse_usize(input: &str) -> IResult<&str, usize> {
@@ -99,3 +114,15 @@ impl Solution<'_, { Day::Day10 }, { Part::One }> for AOC2025<{ Day::Day10 }> {
         Ok(input.par_iter().map(Machine::num_presses).sum())
     }
 }
+
+impl Solution<'_, { Day::Day10 }, { Part::Two }> for AOC2025<{ Day::Day10 }> {
+    type Input = IR;
+    type Output = u64;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+        input
+            .par_iter()
+            .map(|machine| machine.num_joltage_presses().context("unreachable joltage"))
+            .sum()
+    }
+}
@@ -1,12 +1,33 @@
 use super::AOC2025;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use aoc_runner::parse::{lines_of, paragraphs, parse_all, space_separated, unsigned};
+use aoc_runner::shapes::{Shape, fits};
 use aoc_runner::{Day, ParseInput, Part, Solution};
+use nom::IResult;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::combinator::map;
+use nom::sequence::{separated_pair, terminated};
 
-type Shape = Vec<Vec<bool>>;
 pub struct Region {
-    length: u32,
-    width: u32,
-    quantities: Vec<u32>,
+    length: usize,
+    width: usize,
+    quantities: Vec<usize>,
+}
+
+fn region(input: &str) -> IResult<&str, Region> {
+    map(
+        separated_pair(
+            separated_pair(unsigned, char('x'), unsigned),
+            tag(": "),
+            space_separated(unsigned),
+        ),
+        |((width, length), quantities)| Region {
+            length,
+            width,
+            quantities,
+        },
+    )(input)
 }
 
 pub struct IR {
@@ -20,7 +41,30 @@ impl ParseInput<'_, { Day::Day12 }> for AOC2025<{ Day::Day12 }> {
     type Parsed = IR;
 
     fn parse_input(&self, input: &'_ str) -> Result<Self::Parsed> {
-        todo!();
+        let mut trees = Vec::new();
+        let mut regions = Vec::new();
+        for paragraph in paragraphs(input) {
+            let (first, rest) = paragraph.split_once('\n').unwrap_or((paragraph, ""));
+            if first.ends_with(':') {
+                let (_, index) =
+                    terminated(unsigned::<usize>, char(':'))(first).map_err(|e| e.to_owned())?;
+                anyhow::ensure!(index == trees.len(), "shape {index} is out of order");
+                trees.push(rest.parse().with_context(|| format!("shape {index}"))?);
+            } else {
+                regions.extend(parse_all(lines_of(region), paragraph)?);
+            }
+        }
+        for region in &regions {
+            anyhow::ensure!(
+                region.quantities.len() == trees.len(),
+                "region {}x{} lists {} quantities for {} shapes",
+                region.width,
+                region.length,
+                region.quantities.len(),
+                trees.len()
+            );
+        }
+        Ok(IR { trees, regions })
     }
 }
 
@@ -29,6 +73,17 @@ impl Solution<'_, { Day::Day12 }, { Part::One }> for AOC2025<{ Day::Day12 }> {
     type Output = Num;
 
     fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
-        todo!();
+        Ok(input
+            .regions
+            .iter()
+            .filter(|region| {
+                fits(
+                    &input.trees,
+                    &region.quantities,
+                    region.width,
+                    region.length,
+                )
+            })
+            .count())
     }
 }
@@ -20,15 +20,15 @@ use anyhow::Result;
 use anyhow::anyhow;
 use aoc_runner::Day;
 use aoc_runner::SolutionRunner;
+use aoc_runner::input::read_input;
 use aoc_runner::run_solutions;
-use std::fs::read_to_string;
 
 pub struct AOC2025<const DAY: Day>;
 
 fn input(day: &Day) -> Result<String> {
     let day: u8 = (*day).into();
     let path = format!("aoc-2025/input/day{day}.txt");
-    read_to_string(&path).context(path)
+    read_input(&path).context(path)
 }
 
 fn solve(day: &Day) -> Result<()> {
@@ -0,0 +1,402 @@
+use crate::grid::Grid;
+use crate::point2d::Point2D;
+use crate::sparse::SparseGrid;
+#[cfg(feature = "animation")]
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+/// PNG and GIF output need the `animation` feature, which pulls in their encoders.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// One binary PPM file per frame.
+    Ppm,
+    /// One PNG file per frame.
+    #[cfg(feature = "animation")]
+    Png,
+    /// A single animated GIF, showing each frame for `delay` hundredths of a second.
+    #[cfg(feature = "animation")]
+    Gif { delay: u16 },
+}
+
+/// `n` as a narrower integer, or an error naming the dimension which doesn't fit.
+#[cfg(feature = "animation")]
+fn dimension<T: TryFrom<usize>>(n: usize, name: &str, format: &str) -> io::Result<T> {
+    T::try_from(n).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("image {name} {n} is too large for {format}"),
+        )
+    })
+}
+
+/// A picture with one colour per pixel.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    /// One pixel per cell of `grid`, coloured by `palette`.
+    pub fn from_grid<T>(grid: &Grid<T>, palette: impl Fn(&T) -> Rgb) -> Self {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(_, cell)| palette(cell)).collect(),
+        }
+    }
+
+    /// One pixel per cell of `grid` from `min` to `max` inclusive, coloured by `palette`.
+    pub fn from_sparse<T>(
+        grid: &SparseGrid<T>,
+        min: Point2D<i64>,
+        max: Point2D<i64>,
+        palette: impl Fn(Option<&T>) -> Rgb,
+    ) -> Self {
+        let mut pixels = Vec::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                pixels.push(palette(grid.get(Point2D::new(x, y))));
+            }
+        }
+        Self {
+            width: (max.x - min.x + 1).max(0) as usize,
+            height: (max.y - min.y + 1).max(0) as usize,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, p: Point2D<usize>) -> Option<Rgb> {
+        (p.x < self.width && p.y < self.height).then(|| self.pixels[p.y * self.width + p.x])
+    }
+
+    /// Panics if `p` is outside the image.
+    pub fn set(&mut self, p: Point2D<usize>, colour: Rgb) {
+        assert!(
+            p.x < self.width && p.y < self.height,
+            "{p:?} is outside the image"
+        );
+        self.pixels[p.y * self.width + p.x] = colour;
+    }
+
+    /// Each pixel blown up into a `factor` by `factor` square.
+    pub fn scaled(&self, factor: usize) -> Self {
+        let mut pixels = Vec::with_capacity(self.pixels.len() * factor * factor);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let scaled_row: Vec<Rgb> = row
+                .iter()
+                .flat_map(|&p| std::iter::repeat_n(p, factor))
+                .collect();
+            for _ in 0..factor {
+                pixels.extend_from_slice(&scaled_row);
+            }
+        }
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.bytes())
+    }
+
+    #[cfg(feature = "animation")]
+    pub fn write_png(&self, out: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(
+            out,
+            dimension(self.width, "width", "PNG")?,
+            dimension(self.height, "height", "PNG")?,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.bytes())
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    /// The image as palette indices, for a GIF frame. Fails beyond 256 distinct colours.
+    #[cfg(feature = "animation")]
+    fn indexed(&self) -> io::Result<(Vec<u8>, Vec<u8>)> {
+        let mut palette: HashMap<Rgb, u8> = HashMap::new();
+        let mut colours = Vec::new();
+        let mut indices = Vec::with_capacity(self.pixels.len());
+        for &pixel in &self.pixels {
+            let index = match palette.get(&pixel) {
+                Some(&index) => index,
+                None => {
+                    let index = u8::try_from(palette.len())
+                        .map_err(|_| io::Error::other("more than 256 colours in one frame"))?;
+                    palette.insert(pixel, index);
+                    colours.extend_from_slice(&pixel);
+                    index
+                }
+            };
+            indices.push(index);
+        }
+        Ok((indices, colours))
+    }
+}
+
+/// Captures successive states of a simulation as images. A disabled recorder never calls the
+/// drawing closure, so recording calls can stay in solutions at no cost.
+pub struct Recorder {
+    output: Option<Output>,
+    scale: usize,
+    frames: usize,
+}
+
+struct Output {
+    directory: PathBuf,
+    format: Format,
+    #[cfg(feature = "animation")]
+    gif: Option<Animation>,
+}
+
+#[cfg(feature = "animation")]
+struct Animation {
+    encoder: gif::Encoder<BufWriter<File>>,
+    /// The size of the first frame, which every later frame must match.
+    size: (u16, u16),
+}
+
+impl Recorder {
+    pub fn disabled() -> Self {
+        Self {
+            output: None,
+            scale: 1,
+            frames: 0,
+        }
+    }
+
+    /// Records into `directory`, creating it if needed.
+    pub fn new(directory: impl AsRef<Path>, format: Format) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+        Ok(Self {
+            output: Some(Output {
+                directory: directory.as_ref().to_path_buf(),
+                format,
+                #[cfg(feature = "animation")]
+                gif: None,
+            }),
+            scale: 1,
+            frames: 0,
+        })
+    }
+
+    /// Draws every cell as a `scale` by `scale` square.
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        assert!(scale > 0, "scale must be positive");
+        self.scale = scale;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// The number of frames recorded so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Records the image from `draw`, if recording.
+    pub fn record(&mut self, draw: impl FnOnce() -> Image) -> io::Result<()> {
+        let Some(output) = &mut self.output else {
+            return Ok(());
+        };
+        let mut image = draw();
+        if self.scale > 1 {
+            image = image.scaled(self.scale);
+        }
+        let frame_path = |extension| {
+            output
+                .directory
+                .join(format!("frame_{:05}.{extension}", self.frames))
+        };
+        match output.format {
+            Format::Ppm => {
+                image.write_ppm(&mut BufWriter::new(File::create(frame_path("ppm"))?))?
+            }
+            #[cfg(feature = "animation")]
+            Format::Png => image.write_png(BufWriter::new(File::create(frame_path("png"))?))?,
+            #[cfg(feature = "animation")]
+            Format::Gif { delay } => {
+                let size = (
+                    dimension(image.width, "width", "GIF")?,
+                    dimension(image.height, "height", "GIF")?,
+                );
+                if output.gif.is_none() {
+                    let file =
+                        BufWriter::new(File::create(output.directory.join("animation.gif"))?);
+                    let mut encoder =
+                        gif::Encoder::new(file, size.0, size.1, &[]).map_err(io::Error::other)?;
+                    encoder
+                        .set_repeat(gif::Repeat::Infinite)
+                        .map_err(io::Error::other)?;
+                    output.gif = Some(Animation { encoder, size });
+                }
+                let animation = output.gif.as_mut().unwrap();
+                let first = animation.size;
+                if size != first {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "frame {} is {}x{}, but the animation is {}x{}",
+                            self.frames, size.0, size.1, first.0, first.1
+                        ),
+                    ));
+                }
+                let (indices, palette) = image.indexed()?;
+                let mut frame =
+                    gif::Frame::from_palette_pixels(size.0, size.1, indices, palette, None);
+                frame.delay = delay;
+                animation
+                    .encoder
+                    .write_frame(&frame)
+                    .map_err(io::Error::other)?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Flushes any animation still being written.
+    pub fn finish(self) -> io::Result<()> {
+        #[cfg(feature = "animation")]
+        if let Some(animation) = self.output.and_then(|output| output.gif) {
+            animation
+                .encoder
+                .into_inner()
+                .map_err(io::Error::other)?
+                .flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("aoc-animation-{name}"));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn test_image() {
+        let grid = Grid::from_rows(vec![vec![true, false], vec![false, false]]).unwrap();
+        let image = Image::from_grid(&grid, |&on| if on { WHITE } else { BLACK });
+        assert_eq!(image.get(Point2D::new(0, 0)), Some(WHITE));
+        let scaled = image.scaled(2);
+        assert_eq!((scaled.width(), scaled.height()), (4, 4));
+        assert_eq!(scaled.get(Point2D::new(1, 1)), Some(WHITE));
+        assert_eq!(scaled.get(Point2D::new(2, 1)), Some(BLACK));
+
+        let sparse: SparseGrid<()> = [(Point2D::new(-1, 0), ())].into_iter().collect();
+        let image = Image::from_sparse(&sparse, Point2D::new(-1, 0), Point2D::new(0, 0), |c| {
+            if c.is_some() {
+                WHITE
+            } else {
+                BLACK
+            }
+        });
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert_eq!(ppm, b"P6\n2 1\n255\n\xff\xff\xff\0\0\0");
+    }
+
+    #[test]
+    fn test_recorder() -> io::Result<()> {
+        let mut called = false;
+        let mut off = Recorder::disabled();
+        off.record(|| {
+            called = true;
+            Image::new(1, 1, BLACK)
+        })?;
+        assert!(!called && off.frames() == 0);
+
+        let ppm = directory("ppm");
+        let mut recorder = Recorder::new(&ppm, Format::Ppm)?.with_scale(3);
+        recorder.record(|| Image::new(1, 1, WHITE))?;
+        recorder.record(|| Image::new(1, 1, BLACK))?;
+        recorder.finish()?;
+        assert_eq!(
+            fs::read(ppm.join("frame_00001.ppm"))?[..11],
+            *b"P6\n3 3\n255\n"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "animation")]
+    #[test]
+    fn test_encoded_recorder() -> io::Result<()> {
+        let png = directory("png");
+        let mut recorder = Recorder::new(&png, Format::Png)?;
+        recorder.record(|| Image::new(2, 1, WHITE))?;
+        let decoder = png::Decoder::new(File::open(png.join("frame_00000.png"))?);
+        let info = decoder.read_info().map_err(io::Error::other)?;
+        assert_eq!((info.info().width, info.info().height), (2, 1));
+
+        let animation = directory("gif");
+        let mut recorder = Recorder::new(&animation, Format::Gif { delay: 10 })?;
+        for i in 0..3u8 {
+            recorder.record(|| Image::new(2, 2, [i, i, i]))?;
+        }
+        let error = recorder.record(|| Image::new(3, 2, BLACK)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let error = recorder
+            .record(|| Image::new(70_000, 1, BLACK))
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        recorder.finish()?;
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(animation.join("animation.gif"))?)
+            .map_err(io::Error::other)?;
+        let mut frames = 0;
+        while decoder
+            .read_next_frame()
+            .map_err(io::Error::other)?
+            .is_some()
+        {
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,401 @@
+use crate::grid::Grid;
+use crate::point2d::Point2D;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+const BITS: usize = u64::BITS as usize;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid rule {0:?}, expected something like \"B3/S23\"")]
+pub struct ParseRuleError(String);
+
+/// A Life-like rule: which neighbour counts bring a dead cell to life and which keep a live
+/// one alive.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LifeRule {
+    birth: u16,
+    survive: u16,
+}
+
+impl LifeRule {
+    pub const CONWAY: LifeRule = LifeRule {
+        birth: 1 << 3,
+        survive: (1 << 2) | (1 << 3),
+    };
+
+    pub fn new(birth: &[u8], survive: &[u8]) -> Self {
+        let mask = |counts: &[u8]| counts.iter().fold(0, |mask, &n| mask | (1 << n));
+        Self {
+            birth: mask(birth),
+            survive: mask(survive),
+        }
+    }
+}
+
+impl std::str::FromStr for LifeRule {
+    type Err = ParseRuleError;
+
+    /// Parses the `B3/S23` notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRuleError(s.to_string());
+        let (birth, survive) = s.split_once('/').ok_or_else(error)?;
+        let counts = |part: &str, prefix: char| {
+            part.strip_prefix(prefix)
+                .ok_or_else(error)?
+                .chars()
+                .map(|c| match c.to_digit(10) {
+                    Some(n) if n <= 8 => Ok(n as u8),
+                    _ => Err(error()),
+                })
+                .collect::<Result<Vec<u8>, _>>()
+        };
+        Ok(Self::new(&counts(birth, 'B')?, &counts(survive, 'S')?))
+    }
+}
+
+/// A fixed-size grid of booleans packed 64 to a word, for cellular automata and light
+/// switching puzzles where a `Vec<Vec<bool>>` or `HashSet` is too slow.
+///
+/// Bits past `width` in each row are always kept clear.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(BITS);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// Mask of the columns `from..=to` which fall within word `w` of a row.
+    fn span_mask(w: usize, from: usize, to: usize) -> u64 {
+        let lo = from.saturating_sub(w * BITS).min(BITS);
+        let hi = (to + 1).saturating_sub(w * BITS).min(BITS);
+        if lo >= hi {
+            0
+        } else {
+            (u64::MAX >> (BITS - (hi - lo))) << lo
+        }
+    }
+
+    fn last_word_mask(&self) -> u64 {
+        match self.width % BITS {
+            0 => u64::MAX,
+            r => (1 << r) - 1,
+        }
+    }
+
+    pub fn get(&self, point: Point2D<usize>) -> bool {
+        assert!(point.x < self.width && point.y < self.height);
+        self.row(point.y)[point.x / BITS] & (1 << (point.x % BITS)) != 0
+    }
+
+    pub fn set(&mut self, point: Point2D<usize>, value: bool) {
+        assert!(point.x < self.width && point.y < self.height);
+        let word = &mut self.row_mut(point.y)[point.x / BITS];
+        if value {
+            *word |= 1 << (point.x % BITS);
+        } else {
+            *word &= !(1 << (point.x % BITS));
+        }
+    }
+
+    pub fn toggle(&mut self, point: Point2D<usize>) {
+        assert!(point.x < self.width && point.y < self.height);
+        self.row_mut(point.y)[point.x / BITS] ^= 1 << (point.x % BITS);
+    }
+
+    fn update_rect(
+        &mut self,
+        from: Point2D<usize>,
+        to: Point2D<usize>,
+        update: impl Fn(u64, u64) -> u64,
+    ) {
+        let (from, to) = (from.component_min(to), from.component_max(to));
+        assert!(to.x < self.width && to.y < self.height);
+        for y in from.y..=to.y {
+            let row = self.row_mut(y);
+            let words = row.iter_mut().enumerate();
+            for (w, word) in words.take(to.x / BITS + 1).skip(from.x / BITS) {
+                *word = update(*word, Self::span_mask(w, from.x, to.x));
+            }
+        }
+    }
+
+    /// Sets every cell in the rectangle with inclusive corners `from` and `to`.
+    pub fn fill_rect(&mut self, from: Point2D<usize>, to: Point2D<usize>, value: bool) {
+        if value {
+            self.update_rect(from, to, |word, mask| word | mask);
+        } else {
+            self.update_rect(from, to, |word, mask| word & !mask);
+        }
+    }
+
+    /// Flips every cell in the rectangle with inclusive corners `from` and `to`.
+    pub fn toggle_rect(&mut self, from: Point2D<usize>, to: Point2D<usize>) {
+        self.update_rect(from, to, |word, mask| word ^ mask);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Point2D<usize>> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row(y).iter().enumerate().flat_map(move |(w, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    (word != 0).then(|| {
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Point2D::new(w * BITS + bit, y)
+                    })
+                })
+            })
+        })
+    }
+
+    /// A row shifted so that each cell holds its western (`west`) or eastern neighbour.
+    fn shifted(&self, row: &[u64], west: bool) -> Vec<u64> {
+        (0..self.stride)
+            .map(|w| {
+                if west {
+                    let carry = if w > 0 { row[w - 1] >> (BITS - 1) } else { 0 };
+                    (row[w] << 1) | carry
+                } else {
+                    let carry = row.get(w + 1).map_or(0, |next| next << (BITS - 1));
+                    (row[w] >> 1) | carry
+                }
+            })
+            .collect()
+    }
+
+    /// The number of live neighbours of every cell as four bit planes, least significant
+    /// first, summed a whole word at a time.
+    fn neighbor_planes(&self) -> [Vec<u64>; 4] {
+        let mut planes: [Vec<u64>; 4] = std::array::from_fn(|_| vec![0; self.words.len()]);
+        let empty = vec![0; self.stride];
+        for y in 0..self.height {
+            let above = if y > 0 { self.row(y - 1) } else { &empty };
+            let below = if y + 1 < self.height {
+                self.row(y + 1)
+            } else {
+                &empty
+            };
+            let inputs = [
+                above.to_vec(),
+                self.shifted(above, true),
+                self.shifted(above, false),
+                self.shifted(self.row(y), true),
+                self.shifted(self.row(y), false),
+                below.to_vec(),
+                self.shifted(below, true),
+                self.shifted(below, false),
+            ];
+            for w in 0..self.stride {
+                let i = y * self.stride + w;
+                for input in &inputs {
+                    let mut carry = input[w];
+                    for plane in planes.iter_mut() {
+                        let next = plane[i] & carry;
+                        plane[i] ^= carry;
+                        carry = next;
+                    }
+                }
+            }
+        }
+        planes
+    }
+
+    pub fn neighbor_counts(&self) -> Grid<u8> {
+        let planes = self.neighbor_planes();
+        let mut counts = Grid::new(self.width, self.height, 0);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y * self.stride + x / BITS;
+                counts[Point2D::new(x, y)] = planes
+                    .iter()
+                    .enumerate()
+                    .map(|(bit, plane)| (((plane[i] >> (x % BITS)) & 1) as u8) << bit)
+                    .sum();
+            }
+        }
+        counts
+    }
+
+    /// The next generation under `rule`, treating everything outside the grid as dead.
+    pub fn step(&self, rule: &LifeRule) -> BitGrid {
+        let planes = self.neighbor_planes();
+        let last_word_mask = self.last_word_mask();
+        let mut next = BitGrid::new(self.width, self.height);
+        for (i, word) in next.words.iter_mut().enumerate() {
+            let with_count = |n: usize| {
+                planes
+                    .iter()
+                    .enumerate()
+                    .fold(u64::MAX, |acc, (bit, plane)| {
+                        if n & (1 << bit) != 0 {
+                            acc & plane[i]
+                        } else {
+                            acc & !plane[i]
+                        }
+                    })
+            };
+            let (mut born, mut survives) = (0, 0);
+            for n in 0..=8 {
+                if rule.birth & (1 << n) != 0 {
+                    born |= with_count(n);
+                }
+                if rule.survive & (1 << n) != 0 {
+                    survives |= with_count(n);
+                }
+            }
+            let alive = self.words[i];
+            *word = (born & !alive) | (survives & alive);
+            if i % self.stride == self.stride - 1 {
+                *word &= last_word_mask;
+            }
+        }
+        next
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut bits = BitGrid::new(grid.width(), grid.height());
+        for (point, &value) in grid.iter() {
+            if value {
+                bits.set(point, true);
+            }
+        }
+        bits
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(bits: &BitGrid) -> Self {
+        let mut grid = Grid::new(bits.width, bits.height, false);
+        for point in bits.iter_ones() {
+            grid[point] = true;
+        }
+        grid
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = if self.get(Point2D::new(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use anyhow::anyhow;
+
+    fn parse_bits(input: &str) -> BitGrid {
+        let grid = parse::grid(input, |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(anyhow!("bad cell")),
+        })
+        .unwrap();
+        BitGrid::from(&grid)
+    }
+
+    #[test]
+    fn test_rects() {
+        let mut bits = BitGrid::new(1000, 1000);
+        bits.fill_rect(Point2D::new(0, 0), Point2D::new(999, 999), true);
+        assert_eq!(bits.count_ones(), 1_000_000);
+        bits.toggle_rect(Point2D::new(0, 0), Point2D::new(999, 0));
+        assert_eq!(bits.count_ones(), 999_000);
+        bits.fill_rect(Point2D::new(499, 499), Point2D::new(500, 500), false);
+        assert_eq!(bits.count_ones(), 998_996);
+        bits.toggle_rect(Point2D::new(60, 5), Point2D::new(130, 5));
+        assert_eq!(bits.count_ones(), 998_925);
+        assert!(!bits.get(Point2D::new(63, 5)));
+        assert!(bits.get(Point2D::new(131, 5)));
+    }
+
+    #[test]
+    fn test_neighbor_counts() {
+        let bits = parse_bits("#.#\n...\n###\n");
+        let counts = bits.neighbor_counts();
+        assert_eq!(counts[Point2D::new(1, 1)], 5);
+        assert_eq!(counts[Point2D::new(0, 0)], 0);
+        assert_eq!(counts[Point2D::new(1, 2)], 2);
+    }
+
+    #[test]
+    fn test_step() {
+        // The 2015 day 18 example.
+        let start = parse_bits(".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..\n");
+        let after_four = (0..4).fold(start, |grid, _| grid.step(&LifeRule::CONWAY));
+        assert_eq!(
+            after_four.to_string(),
+            "......\n......\n..##..\n..##..\n......\n......\n"
+        );
+
+        // A blinker on a grid wide enough to span several words.
+        let mut blinker = BitGrid::new(130, 3);
+        for x in [62, 63, 64] {
+            blinker.set(Point2D::new(x, 1), true);
+        }
+        let blinked = blinker.step(&"B3/S23".parse().unwrap());
+        assert_eq!(
+            blinked.iter_ones().collect::<Vec<_>>(),
+            vec![
+                Point2D::new(63, 0),
+                Point2D::new(63, 1),
+                Point2D::new(63, 2)
+            ]
+        );
+        assert_eq!(blinked.step(&LifeRule::CONWAY), blinker);
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        assert_eq!("B3/S23".parse(), Ok(LifeRule::CONWAY));
+        assert_eq!("B36/S23".parse(), Ok(LifeRule::new(&[3, 6], &[2, 3])));
+        assert!("B9/S23".parse::<LifeRule>().is_err());
+        assert!("S23".parse::<LifeRule>().is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use num::{PrimInt, Unsigned};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BitsError {
+    #[error("invalid hex digit {1:?} at offset {0}")]
+    InvalidHex(usize, char),
+    #[error("hex input has an odd number of digits")]
+    OddLength,
+    #[error("wanted {wanted} bits but only {remaining} remain")]
+    UnexpectedEnd { wanted: usize, remaining: usize },
+    #[error("cannot read {bits} bits into a {width} bit integer")]
+    TooWide { bits: usize, width: usize },
+}
+
+/// Decodes a string of hex digits, two per byte, such as `D2FE28`.
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, BitsError> {
+    let digits = hex
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or(BitsError::InvalidHex(i, c))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if digits.len() % 2 != 0 {
+        return Err(BitsError::OddLength);
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/// Reads big-endian bit fields of any width from a byte slice, most significant bit first.
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// The absolute index of the next bit to read.
+    position: usize,
+    /// The absolute index one past the last readable bit.
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            end: bytes.len() * 8,
+        }
+    }
+
+    /// The number of bits read so far, counted from the start of this reader's bytes.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.end - self.position
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    fn check(&self, bits: usize) -> Result<(), BitsError> {
+        if bits > self.remaining() {
+            Err(BitsError::UnexpectedEnd {
+                wanted: bits,
+                remaining: self.remaining(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn bit_at(&self, index: usize) -> bool {
+        self.bytes[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, BitsError> {
+        self.check(1)?;
+        let bit = self.bit_at(self.position);
+        self.position += 1;
+        Ok(bit)
+    }
+
+    /// The next `bits` bits as an unsigned integer, without consuming them.
+    pub fn peek<T: PrimInt + Unsigned>(&self, bits: usize) -> Result<T, BitsError> {
+        let width = T::zero().count_zeros() as usize;
+        if bits > width {
+            return Err(BitsError::TooWide { bits, width });
+        }
+        self.check(bits)?;
+        Ok(
+            (self.position..self.position + bits).fold(T::zero(), |value, index| {
+                let bit = if self.bit_at(index) {
+                    T::one()
+                } else {
+                    T::zero()
+                };
+                // Shifting by one at a time never overflows, even when `bits == width`.
+                value.unsigned_shl(1) | bit
+            }),
+        )
+    }
+
+    /// Consumes the next `bits` bits as an unsigned integer.
+    pub fn read<T: PrimInt + Unsigned>(&mut self, bits: usize) -> Result<T, BitsError> {
+        let value = self.peek(bits)?;
+        self.position += bits;
+        Ok(value)
+    }
+
+    pub fn advance(&mut self, bits: usize) -> Result<(), BitsError> {
+        self.check(bits)?;
+        self.position += bits;
+        Ok(())
+    }
+
+    /// Splits off a reader over the next `bits` bits, e.g. a length-delimited group of
+    /// subpackets, and skips past them in this one.
+    pub fn sub_reader(&mut self, bits: usize) -> Result<BitReader<'a>, BitsError> {
+        self.check(bits)?;
+        let sub = BitReader {
+            bytes: self.bytes,
+            position: self.position,
+            end: self.position + bits,
+        };
+        self.position += bits;
+        Ok(sub)
+    }
+}
+
+impl Iterator for BitReader<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        self.read_bit().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_to_bytes() {
+        assert_eq!(hex_to_bytes("2F14df"), Ok(vec![0x2f, 0x14, 0xdf]));
+        assert_eq!(hex_to_bytes("2F1"), Err(BitsError::OddLength));
+        assert_eq!(hex_to_bytes("2G"), Err(BitsError::InvalidHex(1, 'G')));
+    }
+
+    #[test]
+    fn test_read() {
+        // 110 100 10111 11110 00101 000, the 2021 day 16 literal packet.
+        let bytes = hex_to_bytes("D2FE28").unwrap();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.read::<u8>(3), Ok(6));
+        assert_eq!(reader.peek::<u16>(3), Ok(4));
+        assert_eq!(reader.read::<u64>(3), Ok(4));
+        assert_eq!(reader.read_bit(), Ok(true));
+        assert_eq!(reader.position(), 7);
+        assert_eq!(reader.read::<u32>(14), Ok(0b01_1111_1100_0101));
+        assert_eq!(reader.remaining(), 3);
+        assert_eq!(
+            reader.read::<u8>(4),
+            Err(BitsError::UnexpectedEnd {
+                wanted: 4,
+                remaining: 3
+            })
+        );
+        assert_eq!(
+            reader.read::<u8>(9),
+            Err(BitsError::TooWide { bits: 9, width: 8 })
+        );
+    }
+
+    #[test]
+    fn test_full_width() {
+        let bytes = [0xff; 9];
+        let mut reader = BitReader::new(&bytes);
+        reader.advance(4).unwrap();
+        assert_eq!(reader.read::<u64>(64), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_sub_reader() {
+        let bytes = [0b1010_0000, 0b1100_0000];
+        let mut reader = BitReader::new(&bytes);
+        reader.advance(2).unwrap();
+        let mut sub = reader.sub_reader(8).unwrap();
+        assert_eq!(reader.position(), 10);
+        assert_eq!(sub.remaining(), 8);
+        assert_eq!(sub.read::<u8>(8), Ok(0b1000_0011));
+        assert!(sub.is_empty());
+        assert_eq!(sub.next(), None);
+        assert_eq!(reader.read::<u8>(6), Ok(0));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::point3d::Point3D;
+use std::cmp::{max, min};
+
+type Point = Point3D<i64>;
+
+/// An axis-aligned box of integer cells, with both corners included.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Cuboid {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Cuboid {
+    /// The cuboid spanning two opposite corners, in either order.
+    pub fn new(a: Point, b: Point) -> Self {
+        Self {
+            min: Point3D::new(min(a.x, b.x), min(a.y, b.y), min(a.z, b.z)),
+            max: Point3D::new(max(a.x, b.x), max(a.y, b.y), max(a.z, b.z)),
+        }
+    }
+
+    /// The number of cells inside, which for coordinates up to a million fits comfortably.
+    pub fn volume(&self) -> i64 {
+        (self.max.x - self.min.x + 1)
+            * (self.max.y - self.min.y + 1)
+            * (self.max.z - self.min.z + 1)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let lo = Point3D::new(
+            max(self.min.x, other.min.x),
+            max(self.min.y, other.min.y),
+            max(self.min.z, other.min.z),
+        );
+        let hi = Point3D::new(
+            min(self.max.x, other.max.x),
+            min(self.max.y, other.max.y),
+            min(self.max.z, other.max.z),
+        );
+        (lo.x <= hi.x && lo.y <= hi.y && lo.z <= hi.z).then_some(Cuboid { min: lo, max: hi })
+    }
+
+    pub fn intersects(&self, other: &Cuboid) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The cells of `self` outside `other`, as at most six disjoint cuboids: slabs cut off
+    /// along `x`, then `y`, then `z`.
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut pieces = Vec::with_capacity(6);
+        let mut rest = *self;
+        if rest.min.x < overlap.min.x {
+            pieces.push(Cuboid {
+                max: Point3D::new(overlap.min.x - 1, rest.max.y, rest.max.z),
+                ..rest
+            });
+            rest.min.x = overlap.min.x;
+        }
+        if rest.max.x > overlap.max.x {
+            pieces.push(Cuboid {
+                min: Point3D::new(overlap.max.x + 1, rest.min.y, rest.min.z),
+                ..rest
+            });
+            rest.max.x = overlap.max.x;
+        }
+        if rest.min.y < overlap.min.y {
+            pieces.push(Cuboid {
+                max: Point3D::new(rest.max.x, overlap.min.y - 1, rest.max.z),
+                ..rest
+            });
+            rest.min.y = overlap.min.y;
+        }
+        if rest.max.y > overlap.max.y {
+            pieces.push(Cuboid {
+                min: Point3D::new(rest.min.x, overlap.max.y + 1, rest.min.z),
+                ..rest
+            });
+            rest.max.y = overlap.max.y;
+        }
+        if rest.min.z < overlap.min.z {
+            pieces.push(Cuboid {
+                max: Point3D::new(rest.max.x, rest.max.y, overlap.min.z - 1),
+                ..rest
+            });
+        }
+        if rest.max.z > overlap.max.z {
+            pieces.push(Cuboid {
+                min: Point3D::new(rest.min.x, rest.min.y, overlap.max.z + 1),
+                ..rest
+            });
+        }
+        pieces
+    }
+}
+
+/// A union of cells kept as disjoint cuboids, so volumes are plain sums.
+#[derive(Clone, Debug, Default)]
+pub struct CuboidSet {
+    cuboids: Vec<Cuboid>,
+}
+
+impl CuboidSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of disjoint pieces, not cells.
+    pub fn len(&self) -> usize {
+        self.cuboids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cuboids.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cuboid> {
+        self.cuboids.iter()
+    }
+
+    /// Adds every cell of `cuboid`.
+    pub fn insert(&mut self, cuboid: Cuboid) {
+        self.remove(&cuboid);
+        self.cuboids.push(cuboid);
+    }
+
+    /// Removes every cell of `cuboid`.
+    pub fn remove(&mut self, cuboid: &Cuboid) {
+        if !self.cuboids.iter().any(|c| c.intersects(cuboid)) {
+            return;
+        }
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|c| c.subtract(cuboid))
+            .collect();
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cuboids.iter().any(|c| c.contains(p))
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.cuboids.iter().map(Cuboid::volume).sum()
+    }
+
+    /// The number of cells of the set inside `region`.
+    pub fn volume_within(&self, region: &Cuboid) -> i64 {
+        self.cuboids
+            .iter()
+            .filter_map(|c| c.intersection(region))
+            .map(|c| c.volume())
+            .sum()
+    }
+}
+
+impl FromIterator<Cuboid> for CuboidSet {
+    fn from_iter<I: IntoIterator<Item = Cuboid>>(iter: I) -> Self {
+        let mut set = CuboidSet::new();
+        for cuboid in iter {
+            set.insert(cuboid);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cuboid(min: (i64, i64, i64), max: (i64, i64, i64)) -> Cuboid {
+        Cuboid::new(
+            Point3D::new(min.0, min.1, min.2),
+            Point3D::new(max.0, max.1, max.2),
+        )
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let a = cuboid((10, 10, 10), (12, 12, 12));
+        let b = cuboid((13, 13, 13), (11, 11, 11));
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.intersection(&b), Some(cuboid((11, 11, 11), (12, 12, 12))));
+        assert_eq!(a.intersection(&cuboid((13, 0, 0), (20, 20, 20))), None);
+
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.iter().map(Cuboid::volume).sum::<i64>(), 27 - 8);
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(!p.intersects(&b));
+            assert!(pieces[i + 1..].iter().all(|q| !p.intersects(q)));
+        }
+        assert_eq!(a.subtract(&a), vec![]);
+        assert_eq!(cuboid((1, 1, 1), (1, 1, 1)).subtract(&b).len(), 1);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set = CuboidSet::new();
+        set.insert(cuboid((10, 10, 10), (12, 12, 12)));
+        set.insert(cuboid((11, 11, 11), (13, 13, 13)));
+        assert_eq!(set.volume(), 27 + 19);
+        set.remove(&cuboid((9, 9, 9), (11, 11, 11)));
+        assert_eq!(set.volume(), 27 + 19 - 8);
+        set.insert(cuboid((10, 10, 10), (10, 10, 10)));
+        assert_eq!(set.volume(), 39);
+        assert!(set.contains(Point3D::new(10, 10, 10)));
+        assert!(!set.contains(Point3D::new(11, 11, 10)));
+
+        let big: CuboidSet = [
+            cuboid((-100_000, -100_000, -100_000), (100_000, 100_000, 100_000)),
+            cuboid((0, 0, 0), (200_000, 0, 0)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(big.volume(), 200_001i64.pow(3) + 100_000);
+        assert_eq!(big.volume_within(&cuboid((-1, -1, -1), (1, 1, 1))), 27);
+    }
+}
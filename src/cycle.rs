@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence `x0, f(x0), f(f(x0)), ...` which first repeats after `start` steps and then
+/// repeats every `period` steps.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state is the same as the state at step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Floyd's tortoise and hare, which keeps only a couple of states in memory.
+pub fn floyd<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let once = step(&hare);
+        hare = step(&once);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Brent's algorithm, which usually calls `step` fewer times than `floyd`.
+pub fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Remembers every state until one repeats, returning the cycle and the states from step 0
+/// up to (but excluding) the first repeat.
+pub fn find_cycle<S, F>(initial: S, step: F) -> (Cycle, Vec<S>)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    find_cycle_by_key(initial, step, S::clone)
+}
+
+/// Like `find_cycle` but compares states by `key`, for states carrying data such as a step
+/// counter which shouldn't take part in detecting the repeat.
+pub fn find_cycle_by_key<S, K, F, G>(initial: S, mut step: F, mut key: G) -> (Cycle, Vec<S>)
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&start) = seen.get(&key(&state)) {
+            let period = history.len() - start;
+            return (Cycle { start, period }, history);
+        }
+        seen.insert(key(&state), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps, skipping ahead once the sequence starts repeating.
+pub fn state_at<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    while history.len() < n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                period: history.len() - start,
+            };
+            return history.swap_remove(cycle.equivalent_step(n));
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, ..., 9 followed by the cycle 10, 30, 40, 20.
+    fn step(n: &u64) -> u64 {
+        if *n < 10 {
+            n + 1
+        } else {
+            n * 3 % 50
+        }
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let (cycle, history) = find_cycle(0, step);
+        assert_eq!(floyd(0, step), cycle);
+        assert_eq!(brent(0, step), cycle);
+        assert_eq!(history.len(), cycle.start + cycle.period);
+        assert_eq!(history[cycle.start], step(history.last().unwrap()));
+    }
+
+    #[test]
+    fn test_state_at() {
+        let mut naive = 0;
+        for n in 0..200 {
+            assert_eq!(state_at(0, step, n), naive);
+            naive = step(&naive);
+        }
+    }
+
+    #[test]
+    fn test_by_key() {
+        let (cycle, _) = find_cycle_by_key((0u64, 0usize), |(n, i)| (step(n), i + 1), |s| s.0);
+        assert_eq!(cycle, floyd(0, step));
+    }
+}
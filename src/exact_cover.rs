@@ -0,0 +1,307 @@
+use thiserror::Error;
+
+/// Returned when a search tries more rows than its limit allows.
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[error("gave up after trying {0} rows")]
+pub struct IterationLimit(pub usize);
+
+/// An exact cover problem: choose rows so every primary column is covered exactly once and
+/// every secondary column at most once. Solved with Knuth's Algorithm X on dancing links.
+#[derive(Clone, Debug)]
+pub struct ExactCover {
+    primary: usize,
+    secondary: usize,
+    rows: Vec<Vec<usize>>,
+    limit: Option<usize>,
+}
+
+impl ExactCover {
+    /// Columns `0..primary` are primary and `primary..primary + secondary` are secondary.
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        Self {
+            primary,
+            secondary,
+            rows: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Adds a row covering `columns`, returning its index.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        assert!(
+            columns.iter().all(|&c| c < self.primary + self.secondary),
+            "column out of range"
+        );
+        self.rows.push(columns.to_vec());
+        self.rows.len() - 1
+    }
+
+    /// Stops any search after it has tried `iterations` rows.
+    pub fn with_iteration_limit(mut self, iterations: usize) -> Self {
+        self.limit = Some(iterations);
+        self
+    }
+
+    /// The indices of the rows of the first solution found, if there is one.
+    pub fn first_solution(&self) -> Result<Option<Vec<usize>>, IterationLimit> {
+        let mut found = None;
+        self.search(&mut |solution| {
+            found = Some(solution.to_vec());
+            false
+        })?;
+        Ok(found)
+    }
+
+    /// Every solution, each as the indices of its rows.
+    pub fn all_solutions(&self) -> Result<Vec<Vec<usize>>, IterationLimit> {
+        let mut found = Vec::new();
+        self.search(&mut |solution| {
+            found.push(solution.to_vec());
+            true
+        })?;
+        Ok(found)
+    }
+
+    pub fn count_solutions(&self) -> Result<usize, IterationLimit> {
+        let mut count = 0;
+        self.search(&mut |_| {
+            count += 1;
+            true
+        })?;
+        Ok(count)
+    }
+
+    /// Calls `visit` with each solution until it returns false.
+    fn search(&self, visit: &mut dyn FnMut(&[usize]) -> bool) -> Result<(), IterationLimit> {
+        let mut links = Links::new(self.primary + self.secondary, self.primary, &self.rows);
+        let mut search = Search {
+            links: &mut links,
+            partial: Vec::new(),
+            iterations: 0,
+            limit: self.limit,
+            visit,
+        };
+        search.run().map(|_| ())
+    }
+}
+
+/// The toroidal doubly linked lists. Node 0 is the root, nodes `1..=columns` are the column
+/// headers and the rest are the ones of the matrix.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The header of each node's column.
+    column: Vec<usize>,
+    /// The row of each non-header node.
+    row: Vec<usize>,
+    /// The number of nodes in each header's column.
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new(columns: usize, primary: usize, rows: &[Vec<usize>]) -> Self {
+        let headers = columns + 1;
+        let mut links = Links {
+            left: (0..headers).map(|i| i.wrapping_sub(1)).collect(),
+            right: (0..headers).map(|i| i + 1).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+        };
+        // Only primary headers are linked into the root's list; secondary ones link to
+        // themselves so they never need covering.
+        links.left[0] = primary;
+        links.right[primary] = 0;
+        for header in primary + 1..headers {
+            links.left[header] = header;
+            links.right[header] = header;
+        }
+
+        for (r, columns) in rows.iter().enumerate() {
+            let first = links.column.len();
+            for (i, &c) in columns.iter().enumerate() {
+                let header = c + 1;
+                let node = links.column.len();
+                links.column.push(header);
+                links.row.push(r);
+                links.up.push(links.up[header]);
+                links.down.push(header);
+                let last = links.up[header];
+                links.down[last] = node;
+                links.up[header] = node;
+                links.size[header] += 1;
+                links.left.push(if i == 0 { node } else { node - 1 });
+                links.right.push(first);
+                links.right[node - usize::from(i > 0)] = node;
+                links.left[first] = node;
+            }
+        }
+        links
+    }
+
+    fn cover(&mut self, header: usize) {
+        let (l, r) = (self.left[header], self.right[header]);
+        self.right[l] = r;
+        self.left[r] = l;
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (l, r) = (self.left[header], self.right[header]);
+        self.right[l] = header;
+        self.left[r] = header;
+    }
+}
+
+struct Search<'a> {
+    links: &'a mut Links,
+    partial: Vec<usize>,
+    iterations: usize,
+    limit: Option<usize>,
+    visit: &'a mut dyn FnMut(&[usize]) -> bool,
+}
+
+impl Search<'_> {
+    /// Returns whether to keep searching.
+    fn run(&mut self) -> Result<bool, IterationLimit> {
+        let links = &*self.links;
+        if links.right[0] == 0 {
+            return Ok((self.visit)(&self.partial));
+        }
+        // Branch on the primary column with the fewest options.
+        let mut header = links.right[0];
+        let mut best = header;
+        while header != 0 {
+            if links.size[header] < links.size[best] {
+                best = header;
+            }
+            header = links.right[header];
+        }
+        if links.size[best] == 0 {
+            return Ok(true);
+        }
+
+        self.links.cover(best);
+        let mut r = self.links.down[best];
+        let mut keep_going = true;
+        while r != best && keep_going {
+            self.iterations += 1;
+            if self.limit.is_some_and(|limit| self.iterations > limit) {
+                return Err(IterationLimit(self.iterations - 1));
+            }
+            self.partial.push(self.links.row[r]);
+            let mut j = self.links.right[r];
+            while j != r {
+                self.links.cover(self.links.column[j]);
+                j = self.links.right[j];
+            }
+            keep_going = self.run()?;
+            let mut j = self.links.left[r];
+            while j != r {
+                self.links.uncover(self.links.column[j]);
+                j = self.links.left[j];
+            }
+            self.partial.pop();
+            r = self.links.down[r];
+        }
+        self.links.uncover(best);
+        Ok(keep_going)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Knuth's example from the Dancing Links paper, with columns A to G.
+    fn knuth() -> ExactCover {
+        let mut problem = ExactCover::new(7, 0);
+        for row in [
+            &[2, 4, 5][..],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            problem.add_row(row);
+        }
+        problem
+    }
+
+    fn queens(n: usize) -> ExactCover {
+        // Ranks and files are primary, the diagonals secondary.
+        let diagonals = 2 * n - 1;
+        let mut problem = ExactCover::new(2 * n, 2 * diagonals);
+        for rank in 0..n {
+            for file in 0..n {
+                problem.add_row(&[
+                    rank,
+                    n + file,
+                    2 * n + rank + file,
+                    2 * n + diagonals + rank + n - 1 - file,
+                ]);
+            }
+        }
+        problem
+    }
+
+    #[test]
+    fn test_knuth() {
+        let mut solution = knuth().first_solution().unwrap().unwrap();
+        solution.sort_unstable();
+        assert_eq!(solution, vec![0, 3, 4]);
+        assert_eq!(knuth().count_solutions(), Ok(1));
+
+        let mut unsolvable = ExactCover::new(2, 0);
+        unsolvable.add_row(&[0]);
+        assert_eq!(unsolvable.first_solution(), Ok(None));
+    }
+
+    #[test]
+    fn test_secondary_columns() {
+        assert_eq!(queens(4).all_solutions().unwrap().len(), 2);
+        assert_eq!(queens(6).count_solutions(), Ok(4));
+        assert_eq!(queens(8).count_solutions(), Ok(92));
+    }
+
+    #[test]
+    fn test_iteration_limit() {
+        assert_eq!(
+            queens(8).with_iteration_limit(10).count_solutions(),
+            Err(IterationLimit(10))
+        );
+        assert!(queens(8)
+            .with_iteration_limit(1_000_000)
+            .first_solution()
+            .unwrap()
+            .is_some());
+    }
+}
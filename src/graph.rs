@@ -11,31 +11,232 @@ pub trait Graph {
 
 use disjoint::DisjointSet;
 
-pub fn kruskal_with_limit<G: Graph>(graph: &G, unions: usize) -> DisjointSet {
-    let mut vertices = DisjointSet::with_len(graph.number_vertices());
-    let mut count = 0;
+/// A disjoint set over `0..len` which also keeps the size of every component and how many
+/// components there are.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    sets: DisjointSet,
+    /// Indexed by root; stale for vertices which are no longer roots.
+    sizes: Vec<usize>,
+    components: usize,
+}
 
-    for edge in graph.edges_ordered_by_weight() {
-        if !vertices.is_joined(edge.first_vertex(), edge.second_vertex()) {
-            vertices.join(edge.first_vertex(), edge.second_vertex());
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            sets: DisjointSet::with_len(len),
+            sizes: vec![1; len],
+            components: len,
         }
-        count += 1;
-        if count > unions {
-            break;
+    }
+
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// The representative of the component containing `vertex`.
+    pub fn find(&self, vertex: usize) -> usize {
+        self.sets.root_of(vertex)
+    }
+
+    pub fn connected(&self, first: usize, second: usize) -> bool {
+        self.sets.is_joined(first, second)
+    }
+
+    /// Merges the components of `first` and `second`, returning false if they were already
+    /// the same component.
+    pub fn union(&mut self, first: usize, second: usize) -> bool {
+        let size = self.component_size(first) + self.component_size(second);
+        if !self.sets.join(first, second) {
+            return false;
         }
+        let root = self.find(first);
+        self.sizes[root] = size;
+        self.components -= 1;
+        true
+    }
+
+    pub fn component_size(&self, vertex: usize) -> usize {
+        self.sizes[self.find(vertex)]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Whether every vertex is in one component.
+    pub fn is_connected(&self) -> bool {
+        self.components <= 1
+    }
+
+    /// The vertices of each component.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        self.sets.sets()
+    }
+
+    /// The sizes of the `k` largest components, largest first.
+    pub fn largest_k_components(&self, k: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&vertex| self.find(vertex) == vertex)
+            .map(|root| self.sizes[root])
+            .collect();
+        sizes.sort_unstable_by(|l, r| r.cmp(l));
+        sizes.truncate(k);
+        sizes
+    }
+}
+
+/// Joins the endpoints of the `edges` lightest edges, whether or not they were already
+/// connected. This used to take one edge more than asked for, stopping only once its count
+/// passed the limit.
+pub fn kruskal_with_limit<G: Graph>(graph: &G, edges: usize) -> UnionFind {
+    let mut vertices = UnionFind::new(graph.number_vertices());
+    for edge in graph.edges_ordered_by_weight().into_iter().take(edges) {
+        vertices.union(edge.first_vertex(), edge.second_vertex());
     }
     vertices
 }
 
+/// Yields the edges of a minimum spanning forest lazily, lightest first.
+pub struct Kruskal<E> {
+    edges: std::vec::IntoIter<E>,
+    vertices: UnionFind,
+}
+
+impl<E> Kruskal<E> {
+    /// The components joined by the edges yielded so far.
+    pub fn union_find(&self) -> &UnionFind {
+        &self.vertices
+    }
+}
+
+impl<E: Edge> Iterator for Kruskal<E> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        if self.vertices.is_connected() {
+            return None;
+        }
+        self.edges.by_ref().find(|edge| {
+            self.vertices
+                .union(edge.first_vertex(), edge.second_vertex())
+        })
+    }
+}
+
+pub fn kruskal_iter<G: Graph>(graph: &G) -> Kruskal<G::E> {
+    Kruskal {
+        edges: graph.edges_ordered_by_weight().into_iter(),
+        vertices: UnionFind::new(graph.number_vertices()),
+    }
+}
+
 pub fn kruskal<G: Graph>(graph: &G) -> Vec<G::E> {
-    let mut edges = Vec::new();
-    let mut vertices = DisjointSet::with_len(graph.number_vertices());
+    kruskal_iter(graph).collect()
+}
+
+/// The edge whose addition finally puts every vertex in one component, or `None` if the
+/// graph is disconnected.
+pub fn connecting_edge<G: Graph>(graph: &G) -> Option<G::E> {
+    let mut edges = kruskal_iter(graph);
+    let last = edges.by_ref().last();
+    if edges.union_find().is_connected() {
+        last
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Weighted(usize, usize, u32);
+
+    impl Edge for Weighted {
+        fn first_vertex(&self) -> usize {
+            self.0
+        }
 
-    for edge in graph.edges_ordered_by_weight() {
-        if !vertices.is_joined(edge.first_vertex(), edge.second_vertex()) {
-            vertices.join(edge.first_vertex(), edge.second_vertex());
-            edges.push(edge);
+        fn second_vertex(&self) -> usize {
+            self.1
         }
     }
-    edges
+
+    struct EdgeList(usize, Vec<Weighted>);
+
+    impl Graph for EdgeList {
+        type E = Weighted;
+
+        fn edges_ordered_by_weight(&self) -> Vec<Weighted> {
+            let mut edges = self.1.clone();
+            edges.sort_by_key(|edge| edge.2);
+            edges
+        }
+
+        fn number_vertices(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(1, 2));
+        assert!(!sets.union(0, 2));
+        assert!(sets.union(3, 4));
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.component_size(2), 3);
+        assert!(sets.connected(3, 4));
+        assert!(!sets.connected(2, 5));
+        assert_eq!(sets.largest_k_components(2), vec![3, 2]);
+        assert_eq!(sets.largest_k_components(10), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_kruskal() {
+        let graph = EdgeList(
+            4,
+            vec![
+                Weighted(0, 1, 1),
+                Weighted(1, 2, 2),
+                Weighted(0, 2, 3),
+                Weighted(2, 3, 7),
+                Weighted(1, 3, 5),
+            ],
+        );
+        let weights: Vec<u32> = kruskal(&graph).iter().map(|edge| edge.2).collect();
+        assert_eq!(weights, vec![1, 2, 5]);
+        assert_eq!(connecting_edge(&graph), Some(Weighted(1, 3, 5)));
+        assert_eq!(
+            kruskal_with_limit(&graph, 3).largest_k_components(3),
+            vec![3, 1]
+        );
+
+        let disconnected = EdgeList(3, vec![Weighted(0, 1, 1)]);
+        assert_eq!(connecting_edge(&disconnected), None);
+    }
+
+    #[test]
+    fn test_kruskal_with_limit() {
+        let graph = EdgeList(
+            4,
+            vec![Weighted(0, 1, 1), Weighted(1, 2, 2), Weighted(2, 3, 3)],
+        );
+        // Exactly one edge: taking a second as well would make a component of three.
+        assert_eq!(
+            kruskal_with_limit(&graph, 1).largest_k_components(4),
+            vec![2, 1, 1]
+        );
+        assert_eq!(
+            kruskal_with_limit(&graph, 0).largest_k_components(4),
+            vec![1, 1, 1, 1]
+        );
+    }
 }
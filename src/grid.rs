@@ -0,0 +1,179 @@
+use crate::point2d::Point2D;
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GridError {
+    #[error("row {row} has {found} cells but the first row has {expected}")]
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// A dense, row-major 2D grid indexed by `Point2D { x: column, y: row }` with `y` growing
+/// downwards, matching how puzzle inputs are read.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (row, cols) in rows.into_iter().enumerate() {
+            if cols.len() != width {
+                return Err(GridError::Ragged {
+                    row,
+                    expected: width,
+                    found: cols.len(),
+                });
+            }
+            cells.extend(cols);
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, point: Point2D<usize>) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point2D<usize>) -> Option<&T> {
+        self.in_bounds(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn get_mut(&mut self, point: Point2D<usize>) -> Option<&mut T> {
+        if self.in_bounds(point) {
+            Some(&mut self.cells[point.y * self.width + point.x])
+        } else {
+            None
+        }
+    }
+
+    /// Looks up a signed coordinate, treating anything off the grid as missing.
+    pub fn get_signed(&self, point: Point2D<i64>) -> Option<&T> {
+        self.get(point.try_convert().ok()?)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Point2D<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point2D::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point2D<usize>, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position, in reading order, whose cell satisfies `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point2D<usize>> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// The in-bounds orthogonal neighbours of `point`.
+    pub fn neighbors(&self, point: Point2D<usize>) -> impl Iterator<Item = Point2D<usize>> + '_ {
+        point
+            .checked_neighbors()
+            .filter(move |neighbor| self.in_bounds(*neighbor))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point2D<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point2D<usize>) -> &Self::Output {
+        assert!(self.in_bounds(point), "{point:?} is outside the grid");
+        &self.cells[point.y * self.width + point.x]
+    }
+}
+
+impl<T> IndexMut<Point2D<usize>> for Grid<T> {
+    fn index_mut(&mut self, point: Point2D<usize>) -> &mut Self::Output {
+        assert!(self.in_bounds(point), "{point:?} is outside the grid");
+        &mut self.cells[point.y * self.width + point.x]
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point2D::new(2, 1)], 6);
+        assert_eq!(grid.get(Point2D::new(3, 0)), None);
+        assert_eq!(grid.get_signed(Point2D::new(-1, 0)), None);
+        assert_eq!(grid.find(|&n| n == 5), Some(Point2D::new(1, 1)));
+        assert_eq!(grid.neighbors(Point2D::new(0, 0)).count(), 2);
+        grid[Point2D::new(0, 0)] = 9;
+        assert_eq!(grid.to_string(), "923\n456\n");
+        assert_eq!(
+            Grid::from_rows(vec![vec![1, 2], vec![3]]),
+            Err(GridError::Ragged {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
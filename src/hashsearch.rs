@@ -0,0 +1,143 @@
+use rayon::prelude::*;
+
+/// The `i`th hex digit of `digest`, most significant nibble first.
+pub fn nibble(digest: &[u8], i: usize) -> u8 {
+    let byte = digest[i / 2];
+    if i.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0xf
+    }
+}
+
+/// Whether the hex form of `digest` starts with `nibbles`, e.g. `&[0, 0, 0, 0, 0]` for five
+/// zeros.
+pub fn starts_with_nibbles(digest: &[u8], nibbles: &[u8]) -> bool {
+    nibbles.len() <= digest.len() * 2
+        && nibbles
+            .iter()
+            .enumerate()
+            .all(|(i, &n)| nibble(digest, i) == n)
+}
+
+/// Whether the hex form of `digest` starts with at least `n` zeros.
+pub fn leading_zero_nibbles(digest: &[u8], n: usize) -> bool {
+    n <= digest.len() * 2 && (0..n).all(|i| nibble(digest, i) == 0)
+}
+
+/// Searches the hashes of `salt` followed by the decimal integers from `start` upwards.
+/// Candidates are hashed in parallel a chunk at a time, but matches are always reported in
+/// index order, so results don't depend on scheduling.
+pub struct HashSearch<'a, H, P> {
+    salt: &'a str,
+    hash: H,
+    predicate: P,
+    start: u64,
+    chunk_size: u64,
+}
+
+impl<'a, D, H, P> HashSearch<'a, H, P>
+where
+    D: AsRef<[u8]> + Send,
+    H: Fn(&[u8]) -> D + Sync,
+    P: Fn(&[u8]) -> bool + Sync,
+{
+    pub fn new(salt: &'a str, hash: H, predicate: P) -> Self {
+        Self {
+            salt,
+            hash,
+            predicate,
+            start: 0,
+            chunk_size: 1 << 16,
+        }
+    }
+
+    pub fn with_start(mut self, start: u64) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// How many candidates to hash between checks for enough matches.
+    pub fn with_chunk_size(mut self, chunk_size: u64) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    fn check(&self, i: u64) -> Option<(u64, D)> {
+        let digest = (self.hash)(format!("{}{i}", self.salt).as_bytes());
+        (self.predicate)(digest.as_ref()).then_some((i, digest))
+    }
+
+    /// The lowest matching index and its digest. Loops forever if nothing matches.
+    pub fn first(&self) -> (u64, D) {
+        let mut from = self.start;
+        loop {
+            let to = from + self.chunk_size;
+            if let Some(found) = (from..to).into_par_iter().find_map_first(|i| self.check(i)) {
+                return found;
+            }
+            from = to;
+        }
+    }
+
+    /// The `n` lowest matching indices with their digests, in order.
+    pub fn first_n(&self, n: usize) -> Vec<(u64, D)> {
+        let mut found = Vec::with_capacity(n);
+        let mut from = self.start;
+        while found.len() < n {
+            let to = from + self.chunk_size;
+            let chunk: Vec<_> = (from..to)
+                .into_par_iter()
+                .filter_map(|i| self.check(i))
+                .collect();
+            found.extend(chunk.into_iter().take(n - found.len()));
+            from = to;
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nibbles() {
+        let digest = [0x00, 0x0a, 0xf3];
+        assert_eq!(nibble(&digest, 3), 0xa);
+        assert_eq!(nibble(&digest, 4), 0xf);
+        assert!(leading_zero_nibbles(&digest, 3));
+        assert!(!leading_zero_nibbles(&digest, 4));
+        assert!(!leading_zero_nibbles(&digest, 7));
+        assert!(starts_with_nibbles(&digest, &[0, 0, 0, 0xa, 0xf]));
+        assert!(!starts_with_nibbles(&digest, &[0, 0, 1]));
+    }
+
+    #[test]
+    fn test_search() {
+        let md5 = |bytes: &[u8]| md5::compute(bytes).0;
+        let five_zeros = |digest: &[u8]| leading_zero_nibbles(digest, 5);
+        let search = HashSearch::new("abcdef", md5, five_zeros).with_start(609_000);
+        assert_eq!(search.first().0, 609_043);
+
+        // Any chunk size gives the same answers.
+        let three_zeros = |digest: &[u8]| leading_zero_nibbles(digest, 3);
+        let indices = |chunk_size| {
+            HashSearch::new("abc", md5, three_zeros)
+                .with_chunk_size(chunk_size)
+                .first_n(5)
+                .into_iter()
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        };
+        let expected = indices(1 << 16);
+        assert_eq!(expected.len(), 5);
+        assert!(expected.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(indices(7), expected);
+        assert_eq!(
+            HashSearch::new("abc", md5, three_zeros).first().0,
+            expected[0]
+        );
+    }
+}
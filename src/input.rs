@@ -0,0 +1,172 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+
+static WARN_TRAILING_WHITESPACE: AtomicBool = AtomicBool::new(false);
+
+/// Whether `read_input` should warn about lines ending in spaces or tabs.
+pub fn set_warn_trailing_whitespace(warn: bool) {
+    WARN_TRAILING_WHITESPACE.store(warn, Ordering::Relaxed);
+}
+
+/// Strips a byte order mark and turns `\r\n` and lone `\r` line endings into `\n`, borrowing
+/// when there is nothing to change.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// The 1-based numbers of the lines which end in spaces or tabs.
+pub fn trailing_whitespace_lines(input: &str) -> Vec<usize> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.ends_with([' ', '\t']))
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// Reads a puzzle input file and normalizes it.
+pub fn read_input(path: impl AsRef<Path>) -> io::Result<String> {
+    let input = normalize(&fs::read_to_string(&path)?).into_owned();
+    if WARN_TRAILING_WHITESPACE.load(Ordering::Relaxed) {
+        let lines = trailing_whitespace_lines(&input);
+        if !lines.is_empty() {
+            eprintln!(
+                "Warning: {} has trailing whitespace on lines {lines:?}",
+                path.as_ref().display()
+            );
+        }
+    }
+    Ok(input)
+}
+
+/// Something a day's input must satisfy, checked before it is parsed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Expectation {
+    /// Not empty or only whitespace.
+    NonEmpty,
+    /// At most one line, apart from a trailing newline.
+    SingleLine,
+    /// Every line the same length, as for a grid.
+    Rectangular,
+    /// Only these characters, besides newlines.
+    OnlyChars(&'static str),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InputError {
+    #[error("input is empty")]
+    Empty,
+    #[error("expected a single line, found {0}")]
+    NotSingleLine(usize),
+    #[error("line {line} is {width} characters wide, expected {expected} like line 1")]
+    Ragged {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+    #[error("unexpected {found:?} at line {line}, column {column}")]
+    UnexpectedChar {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+impl Expectation {
+    pub fn check(&self, input: &str) -> Result<(), InputError> {
+        match *self {
+            Expectation::NonEmpty if input.trim().is_empty() => Err(InputError::Empty),
+            Expectation::SingleLine if input.lines().count() > 1 => {
+                Err(InputError::NotSingleLine(input.lines().count()))
+            }
+            Expectation::Rectangular => {
+                let mut widths = input.lines().map(|line| line.chars().count());
+                let expected = widths.next().unwrap_or(0);
+                match widths.position(|width| width != expected) {
+                    Some(i) => Err(InputError::Ragged {
+                        line: i + 2,
+                        width: input.lines().nth(i + 1).unwrap().chars().count(),
+                        expected,
+                    }),
+                    None => Ok(()),
+                }
+            }
+            Expectation::OnlyChars(allowed) => {
+                for (y, line) in input.lines().enumerate() {
+                    if let Some((x, found)) = line
+                        .chars()
+                        .enumerate()
+                        .find(|&(_, c)| !allowed.contains(c))
+                    {
+                        return Err(InputError::UnexpectedChar {
+                            line: y + 1,
+                            column: x + 1,
+                            found,
+                        });
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Checks every expectation in turn, returning the first failure.
+pub fn check(input: &str, expectations: &[Expectation]) -> Result<(), InputError> {
+    expectations.iter().try_for_each(|e| e.check(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("a\nb\n"), Cow::Borrowed("a\nb\n")));
+        assert_eq!(normalize("\u{feff}a\r\nb\rc\r\n"), "a\nb\nc\n");
+        assert_eq!(trailing_whitespace_lines("a \nb\nc\t\n"), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_expectations() {
+        let grid = "#.#\n...\n";
+        assert_eq!(
+            check(grid, &[Expectation::NonEmpty, Expectation::Rectangular]),
+            Ok(())
+        );
+        assert_eq!(
+            check(" \n", &[Expectation::NonEmpty]),
+            Err(InputError::Empty)
+        );
+        assert_eq!(
+            check(grid, &[Expectation::SingleLine]),
+            Err(InputError::NotSingleLine(2))
+        );
+        assert_eq!(
+            check("#.#\n...\n..\n", &[Expectation::Rectangular]),
+            Err(InputError::Ragged {
+                line: 3,
+                width: 2,
+                expected: 3
+            })
+        );
+        assert_eq!(
+            check(grid, &[Expectation::OnlyChars("#")]),
+            Err(InputError::UnexpectedChar {
+                line: 1,
+                column: 2,
+                found: '.'
+            })
+        );
+    }
+}
@@ -1,18 +1,42 @@
 #![allow(incomplete_features)]
 #![feature(adt_const_params)]
+#![feature(array_try_from_fn)]
 #![feature(specialization)]
 
 use anyhow::Result;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use pretty_assertions::{assert_eq, assert_str_eq};
 
-use std::{fmt::Display, marker::ConstParamTy};
+use std::{borrow::Cow, fmt::Display, marker::ConstParamTy};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+pub mod animation;
+pub mod bitgrid;
+pub mod bits;
+pub mod cuboid;
+pub mod cycle;
+pub mod exact_cover;
 pub mod graph;
+pub mod grid;
+pub mod hashsearch;
+pub mod input;
+pub mod linear;
+pub mod math;
+pub mod memo;
+pub mod ocr;
+pub mod parse;
+pub mod point;
 pub mod point2d;
 pub mod point3d;
+pub mod poly;
+pub mod polygon;
+pub mod shapes;
+pub mod sparse;
+pub mod testing;
+pub mod tree;
+pub mod viz;
+pub mod vm;
 
 #[derive(
     PartialEq,
@@ -74,6 +98,26 @@ pub trait ParseInput<'a, const DAY: Day> {
     type Parsed;
 
     fn parse_input(&'a self, input: &'a str) -> Result<Self::Parsed>;
+
+    /// Checked against the input before `parse_input`, for clearer errors than the parser's.
+    fn expectations(&self) -> &'static [input::Expectation] {
+        &[]
+    }
+}
+
+/// Normalizes `input`, checks the solver's expectations and parses it. Parsed values may borrow
+/// from the input for `'a`, so an input which needed normalizing is leaked; that happens at
+/// most once per run or test.
+fn checked_parse<'a, T, const DAY: Day>(solver: &'a T, input: &'a str) -> Result<T::Parsed>
+where
+    T: ParseInput<'a, DAY>,
+{
+    let input: &'a str = match input::normalize(input) {
+        Cow::Borrowed(input) => input,
+        Cow::Owned(input) => Box::leak(input.into_boxed_str()),
+    };
+    input::check(input, solver.expectations())?;
+    solver.parse_input(input)
 }
 
 pub trait SolutionRunner<'a, const DAY: Day> {
@@ -95,7 +139,7 @@ where
         + Solution<'a, DAY, { Part::Two }, Input = <Self as ParseInput<'a, DAY>>::Parsed>,
 {
     fn run(&'a self, input: &'a str) -> Result<()> {
-        let parsed = <Self as ParseInput<DAY>>::parse_input(self, input)?;
+        let parsed = checked_parse::<Self, DAY>(self, input)?;
         let part1 = <Self as Solution<'a, DAY, { Part::One }>>::solve(self, &parsed)?;
         println!("Part One: {part1}");
         let part2 = <Self as Solution<'a, DAY, { Part::Two }>>::solve(self, &parsed)?;
@@ -110,7 +154,7 @@ where
         + Solution<'a, DAY, { Part::One }, Input = <Self as ParseInput<'a, DAY>>::Parsed>,
 {
     default fn run(&'a self, input: &'a str) -> Result<()> {
-        let parsed = <Self as ParseInput<DAY>>::parse_input(self, input)?;
+        let parsed = checked_parse::<Self, DAY>(self, input)?;
         let output = <Self as Solution<'a, DAY, { Part::One }>>::solve(self, &parsed)?;
 
         println!("Part One: {output}");
@@ -125,7 +169,7 @@ where
     U: is_type::Is<Type = T::Output> + std::fmt::Debug + std::cmp::PartialEq + std::fmt::Display,
 {
     default fn test_part1(&'a self, input: &'a str, expected: U) -> Result<()> {
-        let parsed_input = <Self as ParseInput<DAY>>::parse_input(self, input)?;
+        let parsed_input = checked_parse::<Self, DAY>(self, input)?;
         let output = <Self as Solution<'a, DAY, { Part::One }>>::solve(self, &parsed_input)?;
         assert_eq!(output, expected);
         Ok(())
@@ -143,7 +187,7 @@ where
         + std::fmt::Display,
 {
     fn test_part1(&'a self, input: &'a str, expected: U) -> Result<()> {
-        let parsed_input = <Self as ParseInput<DAY>>::parse_input(self, input)?;
+        let parsed_input = checked_parse::<Self, DAY>(self, input)?;
         let output = <Self as Solution<'a, DAY, { Part::One }>>::solve(self, &parsed_input)?;
         assert_eq!(output, expected, "Expected: {expected} Actual: {output}",);
         Ok(())
@@ -157,7 +201,7 @@ where
     U: is_type::Is<Type = T::Output> + std::fmt::Debug + std::cmp::PartialEq + std::fmt::Display,
 {
     default fn test_part2(&'a self, input: &'a str, expected: U) -> Result<()> {
-        let input = <Self as ParseInput<DAY>>::parse_input(self, input)?;
+        let input = checked_parse::<Self, DAY>(self, input)?;
         let output = <Self as Solution<'a, DAY, { Part::Two }>>::solve(self, &input)?;
         assert_eq!(output, expected, "Expected: {expected} Actual: {output}",);
         Ok(())
@@ -171,15 +215,32 @@ where
     U: AsRef<str> + std::fmt::Debug + std::cmp::PartialEq + std::fmt::Display,
 {
     fn test_part2(&'a self, input: &'a str, expected: U) -> Result<()> {
-        let input = <Self as ParseInput<DAY>>::parse_input(self, input)?;
+        let input = checked_parse::<Self, DAY>(self, input)?;
         let output = <Self as Solution<'a, DAY, { Part::Two }>>::solve(self, &input)?;
         assert_str_eq!(output, expected);
         Ok(())
     }
 }
 
+/// Runs one day, given as the first argument, or every day. `--viz` shows visualizations in
+/// the terminal, `--viz-file <path>` writes them to a file and `--warn-whitespace` reports
+/// input lines with trailing whitespace.
 pub fn run_solutions(solver: &dyn Fn(&Day) -> Result<()>) {
-    if let Some(day) = std::env::args().nth(1) {
+    let mut args = std::env::args().skip(1);
+    let mut day = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--viz" => viz::set_sink(Box::new(viz::Writer::terminal())),
+            "--warn-whitespace" => input::set_warn_trailing_whitespace(true),
+            "--viz-file" => {
+                let path = args.next().expect("--viz-file needs a path");
+                let writer = viz::Writer::file(path).expect("unable to create visualization file");
+                viz::set_sink(Box::new(writer));
+            }
+            _ => day = Some(arg),
+        }
+    }
+    if let Some(day) = day {
         let day_num = day.parse::<u8>().expect("unable to parse day");
         let day = Day::try_from(day_num).expect("unable to parse day");
         eprintln!("Running day: {day_num}");
@@ -197,3 +258,38 @@ pub fn run_solutions(solver: &dyn Fn(&Day) -> Result<()>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Grid;
+
+    impl ParseInput<'_, { Day::Day1 }> for Grid {
+        type Parsed = Vec<String>;
+
+        fn parse_input(&self, input: &str) -> Result<Self::Parsed> {
+            Ok(input.lines().map(str::to_string).collect())
+        }
+
+        fn expectations(&self) -> &'static [input::Expectation] {
+            &[input::Expectation::Rectangular]
+        }
+    }
+
+    impl Solution<'_, { Day::Day1 }, { Part::One }> for Grid {
+        type Input = Vec<String>;
+        type Output = String;
+
+        fn solve(&self, input: &Self::Input) -> Result<Self::Output> {
+            Ok(input.concat())
+        }
+    }
+
+    #[test]
+    fn test_normalizes_input() -> Result<()> {
+        Grid.test_part1("\u{feff}#.\r\n.#\r\n", "#..#".to_string())?;
+        assert!(Grid.test_part1("#.\n.\n", String::new()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use num::rational::Ratio;
+use num::{Integer, Signed, Zero};
+
+/// The reduced row echelon form of an augmented system `Ax = b`, computed exactly over the
+/// rationals.
+#[derive(Clone, Debug)]
+pub struct RowEchelon<T> {
+    rows: Vec<Vec<Ratio<T>>>,
+    pivots: Vec<usize>,
+    columns: usize,
+    consistent: bool,
+}
+
+impl<T> RowEchelon<T>
+where
+    T: Integer + Signed + Copy,
+{
+    /// Gauss-Jordan elimination of `a` augmented with `b`. Every row of `a` must have the
+    /// same length as `b` has rows.
+    pub fn new(a: &[Vec<T>], b: &[T]) -> Self {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "A and b must have the same number of rows"
+        );
+        let columns = a.first().map_or(0, Vec::len);
+        let mut rows: Vec<Vec<Ratio<T>>> = a
+            .iter()
+            .zip(b)
+            .map(|(row, &b)| {
+                assert_eq!(row.len(), columns, "A must be rectangular");
+                row.iter()
+                    .chain([&b])
+                    .map(|&n| Ratio::from_integer(n))
+                    .collect()
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        for column in 0..columns {
+            let r = pivots.len();
+            let Some(pivot) = (r..rows.len()).find(|&i| !rows[i][column].is_zero()) else {
+                continue;
+            };
+            rows.swap(r, pivot);
+            let scale = rows[r][column];
+            rows[r].iter_mut().for_each(|n| *n = *n / scale);
+            let pivot_row = rows[r].clone();
+            for (i, row) in rows.iter_mut().enumerate() {
+                let factor = row[column];
+                if i != r && !factor.is_zero() {
+                    for (n, p) in row.iter_mut().zip(&pivot_row).skip(column) {
+                        *n = *n - *p * factor;
+                    }
+                }
+            }
+            pivots.push(column);
+        }
+
+        let consistent = rows[pivots.len()..]
+            .iter()
+            .all(|row| row[columns].is_zero());
+        rows.truncate(pivots.len());
+        Self {
+            rows,
+            pivots,
+            columns,
+            consistent,
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// Columns without a pivot, whose values may be chosen freely.
+    pub fn free_variables(&self) -> Vec<usize> {
+        (0..self.columns)
+            .filter(|c| !self.pivots.contains(c))
+            .collect()
+    }
+
+    /// The full solution vector given a value for each of `free_variables()`, in order.
+    pub fn solve_with(&self, free_values: &[T]) -> Option<Vec<Ratio<T>>> {
+        if !self.consistent {
+            return None;
+        }
+        let free = self.free_variables();
+        assert_eq!(free.len(), free_values.len(), "one value per free variable");
+        let mut solution = vec![Ratio::zero(); self.columns];
+        for (&column, &value) in free.iter().zip(free_values) {
+            solution[column] = Ratio::from_integer(value);
+        }
+        for (row, &pivot) in self.rows.iter().zip(&self.pivots) {
+            solution[pivot] = free.iter().fold(row[self.columns], |acc, &column| {
+                acc - row[column] * solution[column]
+            });
+        }
+        Some(solution)
+    }
+
+    /// The unique solution, if the system has exactly one.
+    pub fn unique_solution(&self) -> Option<Vec<Ratio<T>>> {
+        if self.rank() == self.columns {
+            self.solve_with(&[])
+        } else {
+            None
+        }
+    }
+}
+
+/// Minimises `sum(x)` over non-negative integer `x` with `Ax = b`, trying every free variable
+/// in `0..=bounds[column]`. Returns `None` when no such `x` exists.
+pub fn minimize_sum_with_bounds<T>(a: &[Vec<T>], b: &[T], bounds: &[T]) -> Option<Vec<T>>
+where
+    T: Integer + Signed + Copy,
+{
+    let echelon = RowEchelon::new(a, b);
+    if !echelon.is_consistent() {
+        return None;
+    }
+    let free = echelon.free_variables();
+    let mut best: Option<(T, Vec<T>)> = None;
+    search_free(
+        &echelon,
+        &free,
+        bounds,
+        &mut Vec::new(),
+        T::zero(),
+        &mut best,
+    );
+    best.map(|(_, solution)| solution)
+}
+
+/// Minimises `sum(x)` over non-negative integer `x` with `Ax = b` where `A` and `b` are
+/// non-negative, as in "how many button presses reach these counters". Each variable is
+/// bounded by the smallest `b[i] / A[i][j]` it contributes to.
+pub fn minimize_sum<T>(a: &[Vec<T>], b: &[T]) -> Option<Vec<T>>
+where
+    T: Integer + Signed + Copy,
+{
+    let columns = a.first().map_or(0, Vec::len);
+    let bounds: Vec<T> = (0..columns)
+        .map(|column| {
+            a.iter()
+                .zip(b)
+                .filter(|(row, _)| row[column].is_positive())
+                .map(|(row, &b)| b.div_floor(&row[column]))
+                .min()
+                .unwrap_or_else(T::zero)
+        })
+        .collect();
+    minimize_sum_with_bounds(a, b, &bounds)
+}
+
+fn search_free<T>(
+    echelon: &RowEchelon<T>,
+    free: &[usize],
+    bounds: &[T],
+    chosen: &mut Vec<T>,
+    partial_sum: T,
+    best: &mut Option<(T, Vec<T>)>,
+) where
+    T: Integer + Signed + Copy,
+{
+    if best.as_ref().is_some_and(|(sum, _)| partial_sum >= *sum) {
+        return;
+    }
+    if chosen.len() == free.len() {
+        let Some(solution) = echelon.solve_with(chosen) else {
+            return;
+        };
+        if solution.iter().any(|n| !n.is_integer() || n.is_negative()) {
+            return;
+        }
+        let solution: Vec<T> = solution.iter().map(|n| n.to_integer()).collect();
+        let sum = solution.iter().fold(T::zero(), |acc, &n| acc + n);
+        if best.as_ref().is_none_or(|(best_sum, _)| sum < *best_sum) {
+            *best = Some((sum, solution));
+        }
+        return;
+    }
+    let mut value = T::zero();
+    while value <= bounds[free[chosen.len()]] {
+        chosen.push(value);
+        search_free(echelon, free, bounds, chosen, partial_sum + value, best);
+        chosen.pop();
+        value = value + T::one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_solution() {
+        let echelon = RowEchelon::new(&[vec![94i64, 22], vec![34, 67]], &[8400, 5400]);
+        assert_eq!(
+            echelon.unique_solution(),
+            Some(vec![Ratio::from_integer(80), Ratio::from_integer(40)])
+        );
+        let inconsistent = RowEchelon::new(&[vec![1i64, 1], vec![2, 2]], &[1, 3]);
+        assert!(!inconsistent.is_consistent());
+    }
+
+    #[test]
+    fn test_free_variables() {
+        let echelon = RowEchelon::new(&[vec![1i64, 1, 0], vec![0, 1, 1]], &[3, 5]);
+        assert_eq!(echelon.rank(), 2);
+        assert_eq!(echelon.free_variables(), vec![2]);
+        let solution = echelon.solve_with(&[4]).unwrap();
+        assert_eq!(solution, [2, 1, 4].map(Ratio::from_integer).to_vec());
+    }
+
+    #[test]
+    fn test_minimize_sum() {
+        // Buttons (3) (1,3) (2) (2,3) (0,2) (0,1) with joltages {3,5,4,7}.
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let joltage = [3i64, 5, 4, 7];
+        let a: Vec<Vec<i64>> = (0..joltage.len())
+            .map(|i| buttons.iter().map(|b| b.contains(&i) as i64).collect())
+            .collect();
+        let presses = minimize_sum(&a, &joltage).unwrap();
+        assert_eq!(presses.iter().sum::<i64>(), 10);
+        assert_eq!(minimize_sum(&[vec![2i64]], &[3]), None);
+    }
+}
@@ -0,0 +1,165 @@
+use num::integer::Roots;
+use num::{Integer, Signed};
+
+pub fn gcd_all<T, I>(values: I) -> T
+where
+    T: Integer + Copy,
+    I: IntoIterator<Item = T>,
+{
+    values.into_iter().fold(T::zero(), |acc, n| acc.gcd(&n))
+}
+
+pub fn lcm_all<T, I>(values: I) -> T
+where
+    T: Integer + Copy,
+    I: IntoIterator<Item = T>,
+{
+    values.into_iter().fold(T::one(), |acc, n| acc.lcm(&n))
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd<T>(a: T, b: T) -> (T, T, T)
+where
+    T: Integer + Signed + Copy,
+{
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+    while !r.is_zero() {
+        let q = old_r.div_floor(&r);
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse<T>(a: T, m: T) -> Option<T>
+where
+    T: Integer + Signed + Copy,
+{
+    let (g, x, _) = extended_gcd(a.mod_floor(&m), m);
+    g.is_one().then(|| x.mod_floor(&m))
+}
+
+/// `base^exp mod m` by repeated squaring. `m * m` must fit in `T`.
+pub fn mod_pow<T>(base: T, mut exp: T, m: T) -> T
+where
+    T: Integer + Copy,
+{
+    let two = T::one() + T::one();
+    let mut base = base.mod_floor(&m);
+    let mut result = T::one().mod_floor(&m);
+    while exp > T::zero() {
+        if exp.is_odd() {
+            result = (result * base).mod_floor(&m);
+        }
+        base = (base * base).mod_floor(&m);
+        exp = exp / two;
+    }
+    result
+}
+
+/// Solves the system `x = residue (mod modulus)` for every pair, returning `(x, lcm)` where
+/// `x` is the smallest non-negative solution and `lcm` the combined modulus. The moduli need
+/// not be coprime; `None` means the congruences are inconsistent. As with `mod_pow`, the square
+/// of the combined modulus must fit in `T`, so use `i128` when it nears `i64::MAX`.
+pub fn crt<T, I>(congruences: I) -> Option<(T, T)>
+where
+    T: Integer + Signed + Copy,
+    I: IntoIterator<Item = (T, T)>,
+{
+    congruences
+        .into_iter()
+        .try_fold((T::zero(), T::one()), |(a1, m1), (a2, m2)| {
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = a2 - a1;
+            if !diff.is_multiple_of(&g) {
+                return None;
+            }
+            let step = m2 / g;
+            let k = ((diff / g).mod_floor(&step) * p.mod_floor(&step)).mod_floor(&step);
+            let lcm = m1 * step;
+            Some(((a1 + k * m1).mod_floor(&lcm), lcm))
+        })
+}
+
+/// The largest integer whose square is at most `n`.
+pub fn isqrt<T>(n: T) -> T
+where
+    T: Roots,
+{
+    n.sqrt()
+}
+
+/// All positive divisors of `n` in ascending order.
+pub fn divisors<T>(n: T) -> Vec<T>
+where
+    T: Integer + Copy,
+{
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let mut i = T::one();
+    while i * i <= n {
+        if n.is_multiple_of(&i) {
+            small.push(i);
+            if i * i != n {
+                large.push(n / i);
+            }
+        }
+        i = i + T::one();
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(lcm_all([4u64, 6, 10]), 60);
+        assert_eq!(gcd_all(Vec::<i32>::new()), 0);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, g);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(4i64, 8), None);
+        assert_eq!(
+            20151125 * mod_pow(252533i64, 5, 33554393) % 33554393,
+            17289845
+        );
+        assert_eq!(mod_pow(2u64, 10, 1000), 24);
+        assert_eq!(mod_pow(5u64, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(1i64, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1i64, 4), (2, 6)]), None);
+    }
+
+    #[test]
+    fn test_isqrt_divisors() {
+        assert_eq!(isqrt(99u64), 9);
+        assert_eq!(isqrt(100u64), 10);
+        assert_eq!(divisors(36), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(1), vec![1]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+use thiserror::Error;
+
+/// Returned when computing a key needs the value of that same key.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("cycle in memoized recursion at {0:?}")]
+pub struct CycleError<K: Debug>(pub K);
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// Lookups answered from the cache.
+    pub hits: usize,
+    /// Lookups which had to be computed.
+    pub misses: usize,
+}
+
+/// A cache for a recursive function of one hashable key, which survives between calls so
+/// several queries can share work.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: Stats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn get_cached(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Fixes the value of `key`, e.g. to override one input before recomputing.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.cache.insert(key, value);
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Computes `f(key)`, where `f` gets the values it depends on through
+    /// `Recursion::get` so they are cached too.
+    pub fn solve<E, F>(&mut self, key: K, f: F) -> Result<V, E>
+    where
+        E: From<CycleError<K>>,
+        F: Fn(&mut Recursion<'_, K, V, E>, &K) -> Result<V, E>,
+    {
+        let mut recursion = Recursion {
+            memo: self,
+            in_progress: HashSet::new(),
+            f: &f,
+        };
+        recursion.get(key)
+    }
+}
+
+/// The handle a memoized function uses to recurse.
+pub struct Recursion<'a, K, V, E> {
+    memo: &'a mut Memo<K, V>,
+    in_progress: HashSet<K>,
+    #[allow(clippy::type_complexity)]
+    f: &'a dyn Fn(&mut Recursion<'a, K, V, E>, &K) -> Result<V, E>,
+}
+
+impl<K, V, E> Recursion<'_, K, V, E>
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone,
+    E: From<CycleError<K>>,
+{
+    pub fn get(&mut self, key: K) -> Result<V, E> {
+        if let Some(value) = self.memo.cache.get(&key) {
+            self.memo.stats.hits += 1;
+            return Ok(value.clone());
+        }
+        self.memo.stats.misses += 1;
+        if !self.in_progress.insert(key.clone()) {
+            return Err(CycleError(key).into());
+        }
+        let result = (self.f)(self, &key);
+        self.in_progress.remove(&key);
+        let value = result?;
+        self.memo.cache.insert(key, value.clone());
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fibonacci() {
+        let mut memo = Memo::new();
+        let fib = |rec: &mut Recursion<_, _, CycleError<u64>>, &n: &u64| {
+            if n < 2 {
+                Ok(n)
+            } else {
+                Ok(rec.get(n - 1)? + rec.get(n - 2)?)
+            }
+        };
+        assert_eq!(memo.solve(90, fib), Ok(2880067194370816120));
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 88,
+                misses: 91
+            }
+        );
+        assert_eq!(memo.solve(50, fib), Ok(12586269025));
+        assert_eq!(memo.stats().hits, 89);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut memo: Memo<u8, u8> = Memo::new();
+        let step = |rec: &mut Recursion<_, _, CycleError<u8>>, &n: &u8| match n {
+            0 => Ok(0),
+            _ => rec.get((n + 1) % 4),
+        };
+        assert_eq!(memo.solve(2, step), Ok(0));
+        memo.clear();
+        let looping = |rec: &mut Recursion<_, _, _>, &n: &u8| rec.get((n + 1) % 3 + 1);
+        assert_eq!(memo.solve(1, looping), Err(CycleError(1)));
+        assert!(memo.is_empty());
+    }
+}
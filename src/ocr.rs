@@ -0,0 +1,233 @@
+use crate::point2d::Point2D;
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// The 4x6 font, with blank edge columns trimmed so `I` and `Y` line up with the rest.
+const SMALL_FONT: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The 6x10 font.
+const LARGE_FONT: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OcrError {
+    #[error("no lit pixels to read")]
+    Empty,
+    #[error("letters are {0} pixels tall but only 6 and 10 pixel fonts are known")]
+    UnsupportedHeight(usize),
+    #[error("unrecognised glyphs:{}", .0.iter().map(|(i, glyph)| format!("\n#{i}:\n{glyph}")).collect::<String>())]
+    Unrecognised(Vec<(usize, String)>),
+}
+
+/// Reads block letters drawn with `#` for lit pixels and any other character for unlit ones.
+pub fn recognize(picture: &str) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = picture
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .filter(|row: &Vec<bool>| row.contains(&true))
+        .collect();
+    if rows.is_empty() {
+        return Err(OcrError::Empty);
+    }
+    let font: &[(char, &str)] = match rows.len() {
+        6 => &SMALL_FONT,
+        10 => &LARGE_FONT,
+        height => return Err(OcrError::UnsupportedHeight(height)),
+    };
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+    let mut glyphs = Vec::new();
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+        let glyph: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                (start..x)
+                    .map(|i| if row.get(i) == Some(&true) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        glyphs.push(glyph.join("\n"));
+    }
+
+    let mut letters = String::new();
+    let mut unrecognised = Vec::new();
+    for (i, glyph) in glyphs.into_iter().enumerate() {
+        match font.iter().find(|(_, art)| *art == glyph) {
+            Some((letter, _)) => letters.push(*letter),
+            None => unrecognised.push((i, glyph)),
+        }
+    }
+    if unrecognised.is_empty() {
+        Ok(letters)
+    } else {
+        Err(OcrError::Unrecognised(unrecognised))
+    }
+}
+
+/// Reads block letters from the set of lit pixels, wherever they are positioned.
+pub fn recognize_points<T, I>(points: I) -> Result<String, OcrError>
+where
+    I: IntoIterator<Item = Point2D<T>>,
+    i64: TryFrom<T>,
+{
+    let points: HashSet<Point2D<i64>> = points
+        .into_iter()
+        .filter_map(|point| point.try_convert().ok())
+        .collect();
+    let Some(&first) = points.iter().next() else {
+        return Err(OcrError::Empty);
+    };
+    let (min, max) = points.iter().fold((first, first), |(min, max), &point| {
+        (min.component_min(point), max.component_max(point))
+    });
+    let picture: String = (min.y..=max.y)
+        .map(|y| {
+            (min.x..=max.x)
+                .map(|x| {
+                    if points.contains(&Point2D::new(x, y)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect();
+    recognize(&picture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_font() {
+        let picture = "\
+###..#..#.###..####..##..####.#.....##.
+#..#.#..#.#..#.#....#..#....#.#....#..#
+#..#.####.###..###..#......#..#....#...
+###..#..#.#..#.#....#.##..#...#....#...
+#.#..#..#.#..#.#....#..#.#....#....#..#
+#..#.#..#.###..####..###.####.####..##.";
+        assert_eq!(recognize(picture), Ok("RHBEGZLC".to_string()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let (h, i) = (LARGE_FONT[6].1.lines(), LARGE_FONT[13].1.lines());
+        let picture: Vec<String> = h.zip(i).map(|(h, i)| format!("{h}..{i}")).collect();
+        assert_eq!(recognize(&picture.join("\n")), Ok("HX".to_string()));
+    }
+
+    #[test]
+    fn test_points() {
+        let points = SMALL_FONT[11].1.lines().enumerate().flat_map(|(y, line)| {
+            line.char_indices()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| Point2D::new(x + 10, y + 3))
+        });
+        assert_eq!(recognize_points(points), Ok("O".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(recognize("....\n...."), Err(OcrError::Empty));
+        assert_eq!(recognize("#\n#"), Err(OcrError::UnsupportedHeight(2)));
+        let unknown = "####.#\n#..#.#\n####.#\n#..#.#\n#..#.#\n#..#.#";
+        let Err(OcrError::Unrecognised(glyphs)) = recognize(unknown) else {
+            panic!("expected unrecognised glyphs");
+        };
+        assert_eq!(glyphs.len(), 2);
+        assert_eq!(glyphs[1], (1, "#\n#\n#\n#\n#\n#".to_string()));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::grid::Grid;
+use crate::point2d::{recognize_point2d, Point2D};
+use anyhow::{Context, Result};
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, line_ending, one_of, space0, space1};
+use nom::combinator::{all_consuming, map_res, opt, recognize};
+use nom::error::Error;
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
+use nom::{IResult, Parser};
+use std::str::FromStr;
+
+/// An unsigned integer such as `123`.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// An integer with an optional sign such as `-12` or `+7`.
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// A point written as `x,y`.
+pub fn point2d<T: FromStr>(input: &str) -> IResult<&str, Point2D<T>> {
+    map_res(recognize_point2d, Point2D::from_str)(input)
+}
+
+/// One or more `f` separated by runs of spaces, e.g. `1 2   3`.
+pub fn space_separated<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(space1, f)
+}
+
+/// One or more `f` separated by commas with optional spaces, e.g. `1,2, 3`.
+pub fn comma_separated<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(tuple((space0, char(','), space0)), f)
+}
+
+/// One `f` per line.
+pub fn lines_of<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(line_ending, f)
+}
+
+/// Whitespace separated signed numbers, e.g. the `7  15   30` after `Time:`.
+pub fn numbers<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    preceded(space0, space_separated(signed))(input)
+}
+
+/// `f` after `label:` and any spaces, e.g. `labelled("Time", numbers)`.
+pub fn labelled<'a, O, F>(label: &'a str, f: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    preceded(tuple((tag(label), char(':'), space0)), f)
+}
+
+/// A `key: value` pair.
+pub fn key_value<'a, K, V, F, G>(
+    key: F,
+    value: G,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, V)>
+where
+    F: Parser<&'a str, K, Error<&'a str>>,
+    G: Parser<&'a str, V, Error<&'a str>>,
+{
+    separated_pair(key, tuple((space0, char(':'), space0)), value)
+}
+
+/// Splits the input into blocks separated by blank lines, ignoring surrounding newlines.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|paragraph| paragraph.trim_matches('\n'))
+        .filter(|paragraph| !paragraph.is_empty())
+}
+
+/// Runs `parser` over the whole input, allowing a trailing newline, and converts the nom
+/// error into an owned one so it can be returned with `?`.
+pub fn parse_all<'a, O, F>(parser: F, input: &'a str) -> Result<O>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    let (_, output) =
+        all_consuming(terminated(parser, opt(line_ending)))(input).map_err(|e| e.to_owned())?;
+    Ok(output)
+}
+
+/// Parses one cell per character into a rectangular `Grid`.
+pub fn grid<T, F>(input: &str, mut cell: F) -> Result<Grid<T>>
+where
+    F: FnMut(char) -> Result<T>,
+{
+    let rows = input
+        .lines()
+        .enumerate()
+        .map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(x, c)| cell(c).with_context(|| format!("invalid cell {c:?} at ({x},{y})")))
+                .collect()
+        })
+        .collect::<Result<Vec<Vec<T>>>>()?;
+    Ok(Grid::from_rows(rows)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(unsigned::<u8>("42 rest"), Ok((" rest", 42)));
+        assert!(unsigned::<u8>("-1").is_err());
+        assert!(unsigned::<u8>("256").is_err());
+        assert_eq!(signed::<i32>("-12,"), Ok((",", -12)));
+        assert_eq!(numbers::<i64>("  7  15   -30"), Ok(("", vec![7, 15, -30])));
+        assert_eq!(
+            comma_separated(unsigned::<u32>)("1,2, 3"),
+            Ok(("", vec![1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn test_labelled() {
+        assert_eq!(
+            labelled("Time", numbers::<u32>)("Time:      7  15   30"),
+            Ok(("", vec![7, 15, 30]))
+        );
+        assert_eq!(
+            key_value(digit1, space_separated(unsigned::<u8>))("12: 1 2"),
+            Ok(("", ("12", vec![1, 2])))
+        );
+        assert_eq!(
+            parse_all(lines_of(point2d::<i32>), "1,2\n-3,4\n").unwrap(),
+            vec![Point2D::new(1, 2), Point2D::new(-3, 4)]
+        );
+        assert!(parse_all(lines_of(point2d::<i32>), "1,2\nx").is_err());
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let blocks: Vec<&str> = paragraphs("a\nb\n\nc\n\n\nd\n").collect();
+        assert_eq!(blocks, vec!["a\nb", "c", "d"]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let wall = |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(anyhow!("not a wall or floor")),
+        };
+        let parsed = grid("#.\n.#\n", wall).unwrap();
+        assert!(parsed[Point2D::new(1, 1)]);
+        assert!(!parsed[Point2D::new(1, 0)]);
+        assert!(grid("#.\n.x\n", wall).is_err());
+        assert!(grid("#.\n.\n", wall).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+use crate::point2d::Point2D;
+use crate::point3d::Point3D;
+use num::{Signed, Zero};
+use std::array;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParsePointError {
+    #[error("missing ',' in point {0:?}")]
+    MissingSeparator(String),
+    #[error("invalid coordinate {0:?}")]
+    InvalidCoordinate(String),
+    #[error("expected {expected} coordinates, found {found}")]
+    WrongDimension { expected: usize, found: usize },
+}
+
+/// A point with `N` coordinates, for puzzles in any number of dimensions. `Point2D` and
+/// `Point3D` convert to and from `Point<T, 2>` and `Point<T, 3>`, and do their arithmetic,
+/// parsing and formatting through them.
+#[derive(Copy, Clone, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> Point<T, N> {
+    pub fn new(coordinates: [T; N]) -> Self {
+        Self(coordinates)
+    }
+
+    pub fn coordinates(&self) -> &[T; N] {
+        &self.0
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Point<U, N> {
+        Point(self.0.map(f))
+    }
+}
+
+impl<T, const N: usize> Point<T, N> {
+    fn zip_with(self, other: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        let mut other = other.0.into_iter();
+        // Both arrays have `N` elements, so `other` can't run out.
+        Point(self.0.map(|l| f(l, other.next().unwrap())))
+    }
+}
+
+impl<T: Ord, const N: usize> Point<T, N> {
+    pub fn component_min(self, other: Self) -> Self {
+        self.zip_with(other, T::min)
+    }
+
+    pub fn component_max(self, other: Self) -> Self {
+        self.zip_with(other, T::max)
+    }
+}
+
+impl<T: Signed + Copy, const N: usize> Point<T, N> {
+    /// The `2N` points one step away along a single axis.
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..N).flat_map(move |axis| {
+            [-T::one(), T::one()].map(|step| {
+                let mut p = *self;
+                p.0[axis] = p.0[axis] + step;
+                p
+            })
+        })
+    }
+
+    /// The `3^N - 1` points within one step along every axis, including diagonals.
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        let count = 3usize.pow(N as u32);
+        (0..count)
+            .filter(move |&i| i != count / 2)
+            .map(move |mut i| {
+                let mut p = *self;
+                for coordinate in p.0.iter_mut() {
+                    let step = match i % 3 {
+                        0 => -T::one(),
+                        1 => T::zero(),
+                        _ => T::one(),
+                    };
+                    *coordinate = *coordinate + step;
+                    i /= 3;
+                }
+                p
+            })
+    }
+}
+
+pub fn manhattan_distance<T, const N: usize>(l: &Point<T, N>, r: &Point<T, N>) -> T
+where
+    T: Signed + Copy,
+{
+    (0..N).fold(T::zero(), |sum, i| sum + (l.0[i] - r.0[i]).abs())
+}
+
+pub fn chebyshev_distance<T, const N: usize>(l: &Point<T, N>, r: &Point<T, N>) -> T
+where
+    T: Signed + Ord + Copy,
+{
+    (0..N).fold(T::zero(), |m, i| m.max((l.0[i] - r.0[i]).abs()))
+}
+
+pub fn euclidean_distance_squared<T, const N: usize>(l: &Point<T, N>, r: &Point<T, N>) -> T
+where
+    T: Signed + Copy,
+{
+    let d = *r - *l;
+    d * d
+}
+
+impl<T: Default, const N: usize> Default for Point<T, N> {
+    fn default() -> Self {
+        Point(array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.0[axis]
+    }
+}
+
+impl<T: Add<Output = T>, const N: usize> Add for Point<T, N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, T::add)
+    }
+}
+
+impl<T: Add<Output = T> + Copy, const N: usize> Add<T> for Point<T, N> {
+    type Output = Self;
+    fn add(self, rhs: T) -> Self::Output {
+        self.map(|c| c + rhs)
+    }
+}
+
+impl<T: AddAssign + Copy, const N: usize> AddAssign for Point<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (l, r) in self.0.iter_mut().zip(rhs.0) {
+            *l += r;
+        }
+    }
+}
+
+impl<T: Sub<Output = T>, const N: usize> Sub for Point<T, N> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, T::sub)
+    }
+}
+
+impl<T: SubAssign + Copy, const N: usize> SubAssign for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (l, r) in self.0.iter_mut().zip(rhs.0) {
+            *l -= r;
+        }
+    }
+}
+
+impl<T: Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        self.map(T::neg)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|c| c * rhs)
+    }
+}
+
+/// The dot product.
+impl<T: Mul<Output = T> + Zero + Copy, const N: usize> Mul for Point<T, N> {
+    type Output = T;
+    fn mul(self, rhs: Self) -> Self::Output {
+        (0..N).fold(T::zero(), |sum, i| sum + self.0[i] * rhs.0[i])
+    }
+}
+
+/// Parses `N` comma separated coordinates, e.g. `1,-2,3`, optionally in the parentheses which
+/// `Display` adds.
+impl<T: FromStr, const N: usize> FromStr for Point<T, N> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .unwrap_or(s);
+        let parts: Vec<&str> = inner.split(',').collect();
+        if parts.len() == 1 && N > 1 {
+            return Err(ParsePointError::MissingSeparator(s.to_string()));
+        }
+        let parts: [&str; N] =
+            parts
+                .try_into()
+                .map_err(|parts: Vec<&str>| ParsePointError::WrongDimension {
+                    expected: N,
+                    found: parts.len(),
+                })?;
+        array::try_from_fn(|i| {
+            parts[i]
+                .trim()
+                .parse()
+                .map_err(|_| ParsePointError::InvalidCoordinate(parts[i].to_string()))
+        })
+        .map(Point)
+    }
+}
+
+/// Writes the coordinates in parentheses, separated by commas.
+pub(crate) fn write_coordinates<'a, T: 'a>(
+    f: &mut Formatter<'_>,
+    coordinates: impl IntoIterator<Item = &'a T>,
+    write: impl Fn(&mut Formatter<'_>, &T) -> std::fmt::Result,
+) -> std::fmt::Result {
+    write!(f, "(")?;
+    for (i, c) in coordinates.into_iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write(f, c)?;
+    }
+    write!(f, ")")
+}
+
+impl<T: Display, const N: usize> Display for Point<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_coordinates(f, &self.0, |f, c| write!(f, "{c}"))
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for Point<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_coordinates(f, &self.0, |f, c| write!(f, "{c:?}"))
+    }
+}
+
+/// Implements arithmetic, parsing and formatting for a point type with named coordinate fields
+/// by converting it to and from the `Point` with the same coordinates.
+macro_rules! delegate_to_point {
+    ($name:ident, $n:literal, $($field:ident),+) => {
+        impl<T: std::ops::Add<Output = T>> std::ops::Add for $name<T> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self::Output {
+                ($crate::point::Point::from(self) + $crate::point::Point::from(rhs)).into()
+            }
+        }
+
+        impl<T: std::ops::Add<Output = T> + Copy> std::ops::Add<T> for $name<T> {
+            type Output = Self;
+            fn add(self, rhs: T) -> Self::Output {
+                ($crate::point::Point::from(self) + rhs).into()
+            }
+        }
+
+        impl<T: std::ops::AddAssign + Copy> std::ops::AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                let mut p = $crate::point::Point::from(*self);
+                p += $crate::point::Point::from(rhs);
+                *self = p.into();
+            }
+        }
+
+        impl<T: std::ops::Sub<Output = T>> std::ops::Sub for $name<T> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self::Output {
+                ($crate::point::Point::from(self) - $crate::point::Point::from(rhs)).into()
+            }
+        }
+
+        impl<T: std::ops::SubAssign + Copy> std::ops::SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                let mut p = $crate::point::Point::from(*self);
+                p -= $crate::point::Point::from(rhs);
+                *self = p.into();
+            }
+        }
+
+        impl<T: std::ops::Neg<Output = T>> std::ops::Neg for $name<T> {
+            type Output = Self;
+            fn neg(self) -> Self::Output {
+                (-$crate::point::Point::from(self)).into()
+            }
+        }
+
+        impl<T: std::ops::Mul<Output = T> + Copy> std::ops::Mul<T> for $name<T> {
+            type Output = Self;
+            fn mul(self, rhs: T) -> Self::Output {
+                ($crate::point::Point::from(self) * rhs).into()
+            }
+        }
+
+        impl<T: std::str::FromStr> std::str::FromStr for $name<T> {
+            type Err = $crate::point::ParsePointError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse::<$crate::point::Point<T, $n>>().map(Self::from)
+            }
+        }
+
+        impl<T: std::fmt::Display> std::fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $crate::point::write_coordinates(f, [$(&self.$field),+], |f, c| write!(f, "{c}"))
+            }
+        }
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $crate::point::write_coordinates(f, [$(&self.$field),+], |f, c| write!(f, "{c:?}"))
+            }
+        }
+    };
+}
+
+pub(crate) use delegate_to_point;
+
+impl<T> From<Point2D<T>> for Point<T, 2> {
+    fn from(p: Point2D<T>) -> Self {
+        Point([p.x, p.y])
+    }
+}
+
+impl<T> From<Point<T, 2>> for Point2D<T> {
+    fn from(Point([x, y]): Point<T, 2>) -> Self {
+        Point2D::new(x, y)
+    }
+}
+
+impl<T> From<Point3D<T>> for Point<T, 3> {
+    fn from(p: Point3D<T>) -> Self {
+        Point([p.x, p.y, p.z])
+    }
+}
+
+impl<T> From<Point<T, 3>> for Point3D<T> {
+    fn from(Point([x, y, z]): Point<T, 3>) -> Self {
+        Point3D::new(x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roundtrip;
+    use crate::testing::Arbitrary;
+
+    fn parse<P: FromStr>(s: &str) -> Option<P> {
+        s.parse().ok()
+    }
+
+    roundtrip!(
+        test_roundtrip,
+        |rng, size| Point::<i64, 4>(array::from_fn(|_| i64::arbitrary(rng, size))),
+        parse
+    );
+    roundtrip!(test_roundtrip_2d, Point2D::<usize>::arbitrary, parse);
+    roundtrip!(test_roundtrip_3d, Point3D::<i32>::arbitrary, parse);
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point([1i64, 2, 3, 4]);
+        let b = Point([4i64, 3, 2, 1]);
+        assert_eq!(a + b, Point([5; 4]));
+        assert_eq!(a - b, Point([-3, -1, 1, 3]));
+        assert_eq!(-a * 2, Point([-2, -4, -6, -8]));
+        assert_eq!(a * b, 20);
+        assert_eq!(a + 1, Point([2, 3, 4, 5]));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        c[0] = 9;
+        assert_eq!(c.component_max(a), Point([9, 3, 3, 4]));
+    }
+
+    #[test]
+    fn test_non_copy_coordinates() {
+        let big = |n: i64| num::BigInt::from(n) << 100;
+        let a = Point2D::new(big(1), big(2));
+        let b = Point2D::new(big(3), big(5));
+        assert_eq!(a.clone() + b.clone(), Point2D::new(big(4), big(7)));
+        assert_eq!(b - a, Point2D::new(big(2), big(3)));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point([0i64, 3, -2, 1]);
+        let b = Point([2i64, -1, -2, 0]);
+        assert_eq!(manhattan_distance(&a, &b), 7);
+        assert_eq!(chebyshev_distance(&a, &b), 4);
+        assert_eq!(euclidean_distance_squared(&a, &b), 21);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let origin = Point([0i32; 4]);
+        assert_eq!(origin.neighbors().count(), 8);
+        assert!(origin
+            .neighbors()
+            .all(|p| manhattan_distance(&origin, &p) == 1));
+        let all: Vec<_> = origin.all_neighbors().collect();
+        assert_eq!(all.len(), 80);
+        assert!(!all.contains(&origin));
+        assert!(all.iter().all(|p| chebyshev_distance(&origin, p) == 1));
+        assert_eq!(Point([5i32, 5]).all_neighbors().count(), 8);
+    }
+
+    #[test]
+    fn test_parse_and_convert() {
+        assert_eq!("1, -2,3".parse(), Ok(Point([1i32, -2, 3])));
+        assert_eq!(
+            "1,2".parse::<Point<i32, 3>>(),
+            Err(ParsePointError::WrongDimension {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "1,x".parse::<Point<i32, 2>>(),
+            Err(ParsePointError::InvalidCoordinate("x".to_string()))
+        );
+        assert_eq!(Point([1, -2, 3]).to_string(), "(1,-2,3)");
+        assert_eq!("(1,-2,3)".parse(), Ok(Point([1, -2, 3])));
+        assert_eq!(
+            "(1".parse::<Point<i32, 2>>(),
+            Err(ParsePointError::MissingSeparator("(1".to_string()))
+        );
+
+        let p = Point2D::new(3, 4);
+        assert_eq!(Point::from(p), Point([3, 4]));
+        assert_eq!(Point2D::from(Point::from(p)), p);
+        let q = Point3D::new(1, 2, 3);
+        assert_eq!(Point3D::from(Point::from(q)), q);
+    }
+}
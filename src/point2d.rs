@@ -1,13 +1,12 @@
+pub use crate::point::ParsePointError;
+use crate::point::{self, Point};
 use nom::bytes::complete::tag;
 use nom::character::complete::digit1;
 use nom::combinator::{opt, recognize};
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
-use num::Signed;
+use num::{CheckedAdd, CheckedSub, One, Signed};
 use std::cmp::{max, min};
-use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Add, Mul, Sub};
-use std::str::FromStr;
 
 #[derive(Copy, Clone, Default, Eq, Hash, PartialEq)]
 pub struct Point2D<T> {
@@ -19,117 +18,133 @@ impl<T> Point2D<T> {
     pub fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
-}
 
-pub fn manhattan_distance<T>(l: &Point2D<T>, r: &Point2D<T>) -> T
-where
-    T: Signed + std::cmp::Ord + Copy,
-{
-    max(l.x, r.x).abs_sub(&min(l.x, r.x)) + max(l.y, r.y).abs_sub(&min(l.y, r.y))
+    /// Converts each coordinate into `U`, e.g. `Point2D<usize>` grid indices into `Point2D<i64>`.
+    pub fn try_convert<U>(self) -> Result<Point2D<U>, <U as TryFrom<T>>::Error>
+    where
+        U: TryFrom<T>,
+    {
+        Ok(Point2D {
+            x: U::try_from(self.x)?,
+            y: U::try_from(self.y)?,
+        })
+    }
 }
 
-pub fn manhattan_area_inclusive<T>(l: &Point2D<T>, r: &Point2D<T>) -> T
+impl<T> Point2D<T>
 where
-    T: Signed + std::cmp::Ord + Copy,
+    T: Ord + Copy,
 {
-    (max(l.x, r.x) + num::one()).abs_sub(&min(l.x, r.x))
-        * (max(l.y, r.y) + num::one()).abs_sub(&min(l.y, r.y))
-}
+    pub fn component_min(self, other: Self) -> Self {
+        Self {
+            x: min(self.x, other.x),
+            y: min(self.y, other.y),
+        }
+    }
 
-pub fn recognize_point2d(input: &str) -> IResult<&str, &str> {
-    recognize(separated_pair(
-        preceded(opt(tag("-")), digit1),
-        tag(","),
-        preceded(opt(tag("-")), digit1),
-    ))(input)
+    pub fn component_max(self, other: Self) -> Self {
+        Self {
+            x: max(self.x, other.x),
+            y: max(self.y, other.y),
+        }
+    }
 }
 
-impl<T> FromStr for Point2D<T>
+impl<T> Point2D<T>
 where
-    T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
+    T: Signed + Copy,
 {
-    type Err = std::string::ParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split = s.split_once(',').expect("Failed to find comma");
-        Ok(Point2D {
-            x: split.0.parse().expect("Failed to parse coordinate"),
-            y: split.1.parse().expect("Failed to parse coordinate"),
-        })
+    /// The four orthogonally adjacent points.
+    pub fn neighbors(&self) -> [Self; 4] {
+        let (x, y) = (self.x, self.y);
+        let one = T::one();
+        [
+            Self::new(x, y - one),
+            Self::new(x + one, y),
+            Self::new(x, y + one),
+            Self::new(x - one, y),
+        ]
     }
-}
 
-impl<T> Display for Point2D<T>
-where
-    T: Display,
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.x, self.y)
+    /// All eight surrounding points, including diagonals.
+    pub fn all_neighbors(&self) -> [Self; 8] {
+        let (x, y) = (self.x, self.y);
+        let one = T::one();
+        [
+            Self::new(x - one, y - one),
+            Self::new(x, y - one),
+            Self::new(x + one, y - one),
+            Self::new(x + one, y),
+            Self::new(x + one, y + one),
+            Self::new(x, y + one),
+            Self::new(x - one, y + one),
+            Self::new(x - one, y),
+        ]
     }
-}
 
-impl<T> Debug for Point2D<T>
-where
-    T: Debug,
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({:?},{:?})", self.x, self.y)
+    /// Rotates 90 degrees counter-clockwise about the origin, assuming y points up.
+    pub fn rotate_left(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates 90 degrees clockwise about the origin, assuming y points up.
+    pub fn rotate_right(self) -> Self {
+        Self::new(self.y, -self.x)
     }
 }
 
-impl<T> Add for Point2D<T>
+impl<T> Point2D<T>
 where
-    T: Add<Output = T>,
+    T: CheckedAdd + CheckedSub + One + Copy,
 {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+    /// The orthogonally adjacent points which are representable in `T`, so `usize`
+    /// coordinates on the edge of a grid don't underflow.
+    pub fn checked_neighbors(&self) -> impl Iterator<Item = Self> {
+        let (x, y) = (self.x, self.y);
+        let one = T::one();
+        [
+            y.checked_sub(&one).map(|y| Self::new(x, y)),
+            x.checked_add(&one).map(|x| Self::new(x, y)),
+            y.checked_add(&one).map(|y| Self::new(x, y)),
+            x.checked_sub(&one).map(|x| Self::new(x, y)),
+        ]
+        .into_iter()
+        .flatten()
     }
 }
 
-impl<T> Add<T> for Point2D<T>
+pub fn manhattan_distance<T>(l: &Point2D<T>, r: &Point2D<T>) -> T
 where
-    T: Add<Output = T> + Copy,
+    T: Signed + std::cmp::Ord + Copy,
 {
-    type Output = Self;
-    fn add(self, rhs: T) -> Self::Output {
-        Self {
-            x: self.x + rhs,
-            y: self.y + rhs,
-        }
-    }
+    point::manhattan_distance(&Point::from(*l), &Point::from(*r))
 }
 
-impl<T> Sub for Point2D<T>
+pub fn chebyshev_distance<T>(l: &Point2D<T>, r: &Point2D<T>) -> T
 where
-    T: Sub<Output = T>,
+    T: Signed + std::cmp::Ord + Copy,
 {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
+    point::chebyshev_distance(&Point::from(*l), &Point::from(*r))
 }
 
-impl<T> Mul<T> for Point2D<T>
+pub fn manhattan_area_inclusive<T>(l: &Point2D<T>, r: &Point2D<T>) -> T
 where
-    T: Mul<Output = T> + Copy,
+    T: Signed + std::cmp::Ord + Copy,
 {
-    type Output = Self;
+    (max(l.x, r.x) + num::one()).abs_sub(&min(l.x, r.x))
+        * (max(l.y, r.y) + num::one()).abs_sub(&min(l.y, r.y))
+}
 
-    fn mul(self, rhs: T) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
+pub fn recognize_point2d(input: &str) -> IResult<&str, &str> {
+    recognize(separated_pair(
+        preceded(opt(tag("-")), digit1),
+        tag(","),
+        preceded(opt(tag("-")), digit1),
+    ))(input)
 }
 
+point::delegate_to_point!(Point2D, 2, x, y);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,4 +155,53 @@ mod tests {
         assert_eq!(recognize_point2d("-123,-456\n "), Ok(("\n ", "-123,-456")));
         assert!(recognize_point2d("a,b").is_err());
     }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("3,-4".parse(), Ok(Point2D::<i32>::new(3, -4)));
+        assert_eq!(
+            "3;4".parse::<Point2D<i32>>(),
+            Err(ParsePointError::MissingSeparator("3;4".to_string()))
+        );
+        assert_eq!(
+            "-3,4".parse::<Point2D<usize>>(),
+            Err(ParsePointError::InvalidCoordinate("-3".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let origin = Point2D::<usize>::new(0, 0);
+        assert_eq!(
+            origin.checked_neighbors().collect::<Vec<_>>(),
+            vec![Point2D::new(1, 0), Point2D::new(0, 1)]
+        );
+        assert_eq!(Point2D::new(0i32, 0).all_neighbors().len(), 8);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let p = Point2D::new(2, 1);
+        assert_eq!(p.rotate_left(), Point2D::new(-1, 2));
+        assert_eq!(p.rotate_right(), Point2D::new(1, -2));
+        assert_eq!(p.rotate_left().rotate_right(), p);
+        assert_eq!(-p.rotate_left().rotate_left(), p);
+    }
+
+    #[test]
+    fn test_distances() {
+        let l = Point2D::new(1i64, 5);
+        let r = Point2D::new(4i64, -1);
+        assert_eq!(manhattan_distance(&l, &r), 9);
+        assert_eq!(chebyshev_distance(&l, &r), 6);
+        assert_eq!(l.component_min(r), Point2D::new(1, -1));
+        assert_eq!(l.component_max(r), Point2D::new(4, 5));
+    }
+
+    #[test]
+    fn test_convert() {
+        let p = Point2D::<usize>::new(3, 4);
+        assert_eq!(p.try_convert::<i64>(), Ok(Point2D::new(3i64, 4)));
+        assert!(Point2D::new(-1i64, 0).try_convert::<usize>().is_err());
+    }
 }
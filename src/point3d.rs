@@ -1,8 +1,8 @@
-use num::Signed;
-use std::cmp::{max, min};
-use std::fmt::{Debug, Display, Formatter};
+use crate::point::{self, Point};
+use num::{Signed, Zero};
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::ops::{Add, Mul, Sub};
-use std::str::FromStr;
 
 #[derive(Copy, Clone, Default, Eq, Hash, PartialEq)]
 pub struct Point3D<T> {
@@ -21,9 +21,7 @@ pub fn manhattan_distance<T>(l: &Point3D<T>, r: &Point3D<T>) -> T
 where
     T: Signed + std::cmp::Ord + Copy,
 {
-    max(l.x, r.x).abs_sub(&min(l.x, r.x))
-        + max(l.y, r.y).abs_sub(&min(l.y, r.y))
-        + max(l.z, r.z).abs_sub(&min(l.z, r.z))
+    point::manhattan_distance(&Point::from(*l), &Point::from(*r))
 }
 
 pub fn euclidean_distance_squared<T>(l: Point3D<T>, r: Point3D<T>) -> T
@@ -31,121 +29,152 @@ where
     T: Sub<Output = T> + Mul<Output = T> + Add<Output = T> + Copy,
     T: Signed,
 {
-    (r - l) * (r - l)
+    point::euclidean_distance_squared(&Point::from(l), &Point::from(r))
 }
 
-impl<T> FromStr for Point3D<T>
+point::delegate_to_point!(Point3D, 3, x, y, z);
+
+/// The dot product.
+impl<T> Mul for Point3D<T>
 where
-    T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
+    T: Mul<Output = T> + Zero + Copy,
 {
-    type Err = std::string::ParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split(',');
-        Ok(Point3D {
-            x: split
-                .next()
-                .unwrap()
-                .parse()
-                .expect("Failed to parse coordinate"),
-            y: split
-                .next()
-                .unwrap()
-                .parse()
-                .expect("Failed to parse coordinate"),
-            z: split
-                .next()
-                .unwrap()
-                .parse()
-                .expect("Failed to parse coordinate"),
-        })
+    type Output = T;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Point::from(self) * Point::from(rhs)
     }
 }
 
-impl<T> Display for Point3D<T>
-where
-    T: Display,
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let Point3D { x, y, z } = &self;
-        write!(f, "({x},{y},{z})")
-    }
+/// One of the 24 proper rotations of 3D space which map axes onto axes.
+///
+/// Component `i` of a rotated point is `signs[i]` times component `axes[i]` of the original.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Rotation {
+    axes: [usize; 3],
+    signs: [i8; 3],
 }
 
-impl<T> Debug for Point3D<T>
-where
-    T: Debug,
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let Point3D { x, y, z } = &self;
-        write!(f, "({x:?},{y:?},{z:?})")
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// All 24 rotations, starting with the identity.
+    pub fn iter() -> impl Iterator<Item = Rotation> {
+        const PERMUTATIONS: [([usize; 3], i8); 6] = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+        PERMUTATIONS.into_iter().flat_map(|(axes, parity)| {
+            (0..8u8).filter_map(move |bits| {
+                let signs = [0, 1, 2].map(|i| if bits & (1 << i) == 0 { 1 } else { -1 });
+                (signs.iter().product::<i8>() == parity).then_some(Rotation { axes, signs })
+            })
+        })
     }
-}
 
-impl<T> Add for Point3D<T>
-where
-    T: Add<Output = T>,
-{
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
+    pub fn apply<T>(&self, point: Point3D<T>) -> Point3D<T>
+    where
+        T: Signed + Copy,
+    {
+        let coords = [point.x, point.y, point.z];
+        let [x, y, z] = [0, 1, 2].map(|i| {
+            let c = coords[self.axes[i]];
+            if self.signs[i] < 0 {
+                -c
+            } else {
+                c
+            }
+        });
+        Point3D { x, y, z }
+    }
+
+    /// The rotation which applies `other` first and then `self`.
+    pub fn compose(&self, other: &Rotation) -> Rotation {
+        Rotation {
+            axes: [0, 1, 2].map(|i| other.axes[self.axes[i]]),
+            signs: [0, 1, 2].map(|i| self.signs[i] * other.signs[self.axes[i]]),
         }
     }
-}
 
-impl<T> Add<T> for Point3D<T>
-where
-    T: Add<Output = T> + Copy,
-{
-    type Output = Self;
-    fn add(self, rhs: T) -> Self::Output {
-        Self {
-            x: self.x + rhs,
-            y: self.y + rhs,
-            z: self.z + rhs,
+    pub fn inverse(&self) -> Rotation {
+        let mut inverse = Rotation::IDENTITY;
+        for i in 0..3 {
+            inverse.axes[self.axes[i]] = i;
+            inverse.signs[self.axes[i]] = self.signs[i];
         }
+        inverse
     }
 }
 
-impl<T> Sub for Point3D<T>
+/// Finds a rotation and translation which maps at least `min_common` points of `other`
+/// onto points of `reference`, i.e. `rotation.apply(p) + translation` is in `reference`.
+pub fn align<T>(
+    reference: &[Point3D<T>],
+    other: &[Point3D<T>],
+    min_common: usize,
+) -> Option<(Rotation, Point3D<T>)>
 where
-    T: Sub<Output = T>,
-    T: Signed,
+    T: Signed + Copy + Eq + Hash,
 {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
+    Rotation::iter().find_map(|rotation| {
+        let mut offsets: HashMap<Point3D<T>, usize> = HashMap::new();
+        for point in other {
+            let rotated = rotation.apply(*point);
+            for anchor in reference {
+                let count = offsets.entry(*anchor - rotated).or_default();
+                *count += 1;
+                if *count >= min_common {
+                    return Some((rotation, *anchor - rotated));
+                }
+            }
         }
-    }
+        None
+    })
 }
 
-impl<T> Mul<T> for Point3D<T>
-where
-    T: Mul<Output = T> + Copy,
-{
-    type Output = Self;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
 
-    fn mul(self, rhs: T) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
+    #[test]
+    fn test_rotations() {
+        let p = Point3D::new(1, 2, 3);
+        let images: HashSet<Point3D<i32>> = Rotation::iter().map(|r| r.apply(p)).collect();
+        assert_eq!(images.len(), 24);
+        assert_eq!(Rotation::iter().next(), Some(Rotation::IDENTITY));
+        for r in Rotation::iter() {
+            assert_eq!(r.inverse().apply(r.apply(p)), p);
+            assert_eq!(r.compose(&r.inverse()), Rotation::IDENTITY);
+            for s in Rotation::iter() {
+                assert_eq!(r.compose(&s).apply(p), r.apply(s.apply(p)));
+            }
         }
     }
-}
 
-impl<T> Mul for Point3D<T>
-where
-    T: Mul<Output = T> + Add<Output = T>,
-{
-    type Output = T;
-    fn mul(self, rhs: Self) -> Self::Output {
-        (self.x * rhs.x) + (self.y * rhs.y) + (self.z * rhs.z)
+    #[test]
+    fn test_align() {
+        let reference = vec![
+            Point3D::new(0, 0, 0),
+            Point3D::new(4, 1, 0),
+            Point3D::new(3, 3, 0),
+            Point3D::new(-7, 2, 9),
+        ];
+        let rotation = Rotation::iter().nth(17).unwrap();
+        let translation = Point3D::new(5, -3, 12);
+        let other: Vec<Point3D<i32>> = reference[..3]
+            .iter()
+            .map(|p| rotation.inverse().apply(*p - translation))
+            .chain([Point3D::new(100, 100, 100)])
+            .collect();
+
+        assert_eq!(align(&reference, &other, 3), Some((rotation, translation)));
+        assert_eq!(align(&reference, &other, 4), None);
     }
 }
@@ -0,0 +1,175 @@
+use num::bigint::BigInt;
+use num::rational::BigRational;
+use num::{Signed, ToPrimitive, Zero};
+
+/// The rows of the difference table of `samples`, starting with the samples themselves and
+/// stopping at the first row which is all zero (or has a single entry).
+fn differences<T: Signed + Copy>(samples: &[T]) -> Vec<Vec<T>> {
+    let mut rows = vec![samples.to_vec()];
+    while let Some(row) = rows.last() {
+        if row.len() < 2 || row.iter().all(Zero::is_zero) {
+            break;
+        }
+        let next = row.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        rows.push(next);
+    }
+    rows
+}
+
+/// The value following equally spaced `samples`, by extending the difference table.
+pub fn next_value<T: Signed + Copy>(samples: &[T]) -> Option<T> {
+    samples.last()?;
+    Some(
+        differences(samples)
+            .iter()
+            .filter_map(|row| row.last())
+            .fold(T::zero(), |acc, &last| acc + last),
+    )
+}
+
+/// The value preceding equally spaced `samples`, by extending the difference table
+/// backwards.
+pub fn previous_value<T: Signed + Copy>(samples: &[T]) -> Option<T> {
+    samples.first()?;
+    Some(
+        differences(samples)
+            .iter()
+            .rev()
+            .filter_map(|row| row.first())
+            .fold(T::zero(), |acc, &first| first - acc),
+    )
+}
+
+/// A polynomial with exact rational coefficients, lowest degree first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    coefficients: Vec<BigRational>,
+}
+
+impl Polynomial {
+    pub fn new(coefficients: Vec<BigRational>) -> Self {
+        let mut polynomial = Self { coefficients };
+        polynomial.trim();
+        polynomial
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(Zero::is_zero) {
+            self.coefficients.pop();
+        }
+    }
+
+    /// The polynomial of least degree through `points`, by Lagrange interpolation. Returns
+    /// `None` if two points share an `x`.
+    pub fn interpolate(points: &[(i64, i64)]) -> Option<Self> {
+        let mut coefficients = vec![BigRational::zero(); points.len()];
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            // Expand the basis polynomial yi * prod (x - xj) / (xi - xj) term by term.
+            let mut basis = vec![BigRational::from_integer(BigInt::from(yi))];
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                if xi == xj {
+                    return None;
+                }
+                let denominator = BigRational::from_integer(BigInt::from(xi - xj));
+                let root = BigRational::from_integer(BigInt::from(xj));
+                let mut product = vec![BigRational::zero(); basis.len() + 1];
+                for (k, coefficient) in basis.iter().enumerate() {
+                    let scaled = coefficient / &denominator;
+                    product[k] -= &scaled * &root;
+                    product[k + 1] += scaled;
+                }
+                basis = product;
+            }
+            for (total, term) in coefficients.iter_mut().zip(basis) {
+                *total += term;
+            }
+        }
+        Some(Self::new(coefficients))
+    }
+
+    /// The polynomial through `samples` taken at `x = 0, 1, 2, ...`.
+    pub fn from_samples(samples: &[i64]) -> Self {
+        let points: Vec<(i64, i64)> = (0..).zip(samples.iter().copied()).collect();
+        Self::interpolate(&points).expect("sample positions are distinct")
+    }
+
+    pub fn coefficients(&self) -> &[BigRational] {
+        &self.coefficients
+    }
+
+    /// The degree, taking the zero polynomial to have degree 0.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// The exact value at `x`, by Horner's method.
+    pub fn evaluate(&self, x: i64) -> BigRational {
+        let x = BigRational::from_integer(BigInt::from(x));
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigRational::zero(), |acc, coefficient| {
+                acc * &x + coefficient
+            })
+    }
+
+    /// The value at `x` if it is an integer which fits in an `i64`.
+    pub fn evaluate_i64(&self, x: i64) -> Option<i64> {
+        let value = self.evaluate(x);
+        if value.is_integer() {
+            value.to_integer().to_i64()
+        } else {
+            None
+        }
+    }
+}
+
+/// The value at `x` of the polynomial through `samples` taken at `x = 0, 1, 2, ...`, for
+/// evaluating far beyond the samples without overflowing along the way.
+pub fn extrapolate(samples: &[i64], x: i64) -> Option<i64> {
+    Polynomial::from_samples(samples).evaluate_i64(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_differences() {
+        assert_eq!(next_value(&[0, 3, 6, 9, 12, 15]), Some(18));
+        assert_eq!(next_value(&[10, 13, 16, 21, 30, 45]), Some(68));
+        assert_eq!(previous_value(&[10, 13, 16, 21, 30, 45]), Some(5));
+        assert_eq!(previous_value(&[1, 3, 6, 10, 15, 21]), Some(0));
+        assert_eq!(next_value(&[7]), Some(7));
+        assert_eq!(next_value::<i32>(&[]), None);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // 2x^2 - 3x + 1
+        let polynomial = Polynomial::interpolate(&[(-1, 6), (2, 3), (5, 36)]).unwrap();
+        assert_eq!(polynomial.degree(), 2);
+        assert_eq!(polynomial.evaluate_i64(0), Some(1));
+        assert_eq!(polynomial.evaluate_i64(10), Some(171));
+        assert_eq!(Polynomial::interpolate(&[(1, 1), (1, 2)]), None);
+
+        let half = Polynomial::interpolate(&[(0, 0), (2, 1)]).unwrap();
+        assert_eq!(half.evaluate_i64(1), None);
+        assert_eq!(half.evaluate(1), BigRational::new(1.into(), 2.into()));
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let squares = [0, 1, 4, 9];
+        assert_eq!(
+            extrapolate(&squares, 3_000_000_000),
+            Some(9_000_000_000_000_000_000)
+        );
+        assert_eq!(extrapolate(&squares, -5), Some(25));
+        assert_eq!(extrapolate(&squares, 4_000_000_000), None);
+        assert_eq!(Polynomial::from_samples(&[3, 3, 3]).degree(), 0);
+    }
+}
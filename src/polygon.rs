@@ -0,0 +1,192 @@
+use crate::point2d::Point2D;
+use num::Integer;
+use std::cmp::{max, min};
+
+type Point = Point2D<i64>;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// The edges of a simple polygon given as its vertices in order, closing back to the first.
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&p, &q)| (p, q))
+}
+
+/// Twice the signed area by the shoelace formula: positive for counter-clockwise vertices
+/// when `y` points up.
+pub fn twice_signed_area(vertices: &[Point]) -> i64 {
+    edges(vertices).map(|(p, q)| p.x * q.y - q.x * p.y).sum()
+}
+
+/// Twice the enclosed area, which is always an integer for lattice polygons.
+pub fn twice_area(vertices: &[Point]) -> i64 {
+    twice_signed_area(vertices).abs()
+}
+
+/// The number of lattice points on the edges.
+pub fn boundary_points(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|(p, q)| (q.x - p.x).abs().gcd(&(q.y - p.y).abs()))
+        .sum()
+}
+
+/// The number of lattice points strictly inside, by Pick's theorem.
+pub fn interior_points(vertices: &[Point]) -> i64 {
+    (twice_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+fn on_segment(point: Point, p: Point, q: Point) -> bool {
+    let cross = (q.x - p.x) * (point.y - p.y) - (q.y - p.y) * (point.x - p.x);
+    cross == 0
+        && (min(p.x, q.x)..=max(p.x, q.x)).contains(&point.x)
+        && (min(p.y, q.y)..=max(p.y, q.y)).contains(&point.y)
+}
+
+/// Whether `point` is inside, on the boundary of, or outside the polygon, by casting a ray
+/// towards positive `x`.
+pub fn contains(vertices: &[Point], point: Point) -> Containment {
+    let mut crossings = 0;
+    for (p, q) in edges(vertices) {
+        if on_segment(point, p, q) {
+            return Containment::Boundary;
+        }
+        if (p.y > point.y) != (q.y > point.y) {
+            // Compare point.x with the x where the edge meets the ray, without dividing.
+            let lhs = (point.x - p.x) as i128 * (q.y - p.y) as i128;
+            let rhs = (point.y - p.y) as i128 * (q.x - p.x) as i128;
+            if (q.y > p.y && lhs < rhs) || (q.y < p.y && lhs > rhs) {
+                crossings += 1;
+            }
+        }
+    }
+    if crossings % 2 == 1 {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+fn within(vertices: &[Point], point: Point) -> bool {
+    contains(vertices, point) != Containment::Outside
+}
+
+/// Whether the axis-aligned rectangle with opposite corners `a` and `b` lies entirely within
+/// the closed rectilinear polygon, i.e. one whose edges are all horizontal or vertical.
+pub fn contains_rect(vertices: &[Point], a: Point, b: Point) -> bool {
+    debug_assert!(
+        edges(vertices).all(|(p, q)| p.x == q.x || p.y == q.y),
+        "polygon must be rectilinear"
+    );
+    let (lo, hi) = (a.component_min(b), a.component_max(b));
+    // Work in doubled coordinates so midpoints stay on the lattice.
+    let doubled: Vec<Point> = vertices.iter().map(|&v| v * 2).collect();
+
+    if lo.x == hi.x || lo.y == hi.y {
+        // A segment leaves the polygon only at a vertex coordinate, so checking those and
+        // the midpoints between them is enough.
+        let horizontal = lo.y == hi.y;
+        let along = |p: Point| if horizontal { p.x } else { p.y };
+        let mut stops: Vec<i64> = vertices
+            .iter()
+            .map(|&v| along(v))
+            .filter(|c| (along(lo)..=along(hi)).contains(c))
+            .chain([along(lo), along(hi)])
+            .map(|c| c * 2)
+            .collect();
+        stops.sort_unstable();
+        stops.dedup();
+        let midpoints: Vec<i64> = stops.windows(2).map(|w| (w[0] + w[1]) / 2).collect();
+        return stops.iter().chain(&midpoints).all(|&c| {
+            let point = if horizontal {
+                Point2D::new(c, lo.y * 2)
+            } else {
+                Point2D::new(lo.x * 2, c)
+            };
+            within(&doubled, point)
+        });
+    }
+
+    // Otherwise no edge may pass through the open interior, which then lies entirely on one
+    // side of the boundary, so its centre decides.
+    let crosses = edges(vertices).any(|(p, q)| {
+        if p.x == q.x {
+            lo.x < p.x && p.x < hi.x && min(p.y, q.y) < hi.y && max(p.y, q.y) > lo.y
+        } else {
+            lo.y < p.y && p.y < hi.y && min(p.x, q.x) < hi.x && max(p.x, q.x) > lo.x
+        }
+    });
+    !crosses && within(&doubled, lo + hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Vec<Point> {
+        points.iter().map(|&(x, y)| Point2D::new(x, y)).collect()
+    }
+
+    #[test]
+    fn test_area() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(twice_signed_area(&square), 32);
+        assert_eq!(
+            twice_area(&square.iter().rev().copied().collect::<Vec<_>>()),
+            32
+        );
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+
+        let triangle = polygon(&[(0, 0), (3, 0), (0, 3)]);
+        assert_eq!(twice_area(&triangle), 9);
+        assert_eq!(boundary_points(&triangle), 9);
+        assert_eq!(interior_points(&triangle), 1);
+    }
+
+    #[test]
+    fn test_contains() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(contains(&square, Point2D::new(2, 2)), Containment::Inside);
+        assert_eq!(contains(&square, Point2D::new(4, 1)), Containment::Boundary);
+        assert_eq!(contains(&square, Point2D::new(0, 0)), Containment::Boundary);
+        assert_eq!(contains(&square, Point2D::new(5, 2)), Containment::Outside);
+        assert_eq!(contains(&square, Point2D::new(-1, 4)), Containment::Outside);
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        // The 2025 day 9 example.
+        let red = polygon(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]);
+        assert!(contains_rect(&red, Point2D::new(9, 5), Point2D::new(2, 3)));
+        assert!(contains_rect(&red, Point2D::new(7, 3), Point2D::new(11, 1)));
+        assert!(!contains_rect(
+            &red,
+            Point2D::new(2, 5),
+            Point2D::new(11, 1)
+        ));
+        assert!(!contains_rect(
+            &red,
+            Point2D::new(2, 3),
+            Point2D::new(11, 7)
+        ));
+        assert!(contains_rect(&red, Point2D::new(2, 5), Point2D::new(9, 5)));
+        assert!(contains_rect(&red, Point2D::new(2, 3), Point2D::new(11, 3)));
+        assert!(!contains_rect(&red, Point2D::new(2, 3), Point2D::new(2, 7)));
+    }
+}
@@ -0,0 +1,335 @@
+use crate::point2d::Point2D;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+type Point = Point2D<i64>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseShapeError {
+    #[error("unexpected {0:?} in shape, expected '#' or '.'")]
+    InvalidChar(char),
+    #[error("shape has no cells")]
+    Empty,
+}
+
+/// A polyomino as its set of cells, translated so the smallest `x` and `y` are both zero.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Shape {
+    /// Sorted in reading order, by `y` and then `x`.
+    cells: Vec<Point>,
+}
+
+impl Shape {
+    pub fn from_cells(cells: impl IntoIterator<Item = Point>) -> Result<Self, ParseShapeError> {
+        let cells: BTreeSet<(i64, i64)> = cells.into_iter().map(|p| (p.y, p.x)).collect();
+        let min_y = cells
+            .iter()
+            .map(|&(y, _)| y)
+            .min()
+            .ok_or(ParseShapeError::Empty)?;
+        let min_x = cells
+            .iter()
+            .map(|&(_, x)| x)
+            .min()
+            .ok_or(ParseShapeError::Empty)?;
+        Ok(Self {
+            cells: cells
+                .into_iter()
+                .map(|(y, x)| Point2D::new(x - min_x, y - min_y))
+                .collect(),
+        })
+    }
+
+    pub fn cells(&self) -> &[Point] {
+        &self.cells
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> i64 {
+        self.cells.iter().map(|p| p.x).max().unwrap_or(-1) + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.cells.iter().map(|p| p.y).max().unwrap_or(-1) + 1
+    }
+
+    /// The shape turned a quarter clockwise, with `y` pointing down.
+    pub fn rotate(&self) -> Self {
+        Self::from_cells(self.cells.iter().map(|p| Point2D::new(-p.y, p.x))).unwrap()
+    }
+
+    /// The shape mirrored left to right.
+    pub fn reflect(&self) -> Self {
+        Self::from_cells(self.cells.iter().map(|p| Point2D::new(-p.x, p.y))).unwrap()
+    }
+
+    /// The distinct rotations and reflections of the shape, starting with itself.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations: Vec<Self> = Vec::with_capacity(8);
+        for mut shape in [self.clone(), self.reflect()] {
+            for _ in 0..4 {
+                if !orientations.contains(&shape) {
+                    orientations.push(shape.clone());
+                }
+                shape = shape.rotate();
+            }
+        }
+        orientations
+    }
+}
+
+impl FromStr for Shape {
+    type Err = ParseShapeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cells = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => cells.push(Point2D::new(x as i64, y as i64)),
+                    '.' => (),
+                    _ => return Err(ParseShapeError::InvalidChar(c)),
+                }
+            }
+        }
+        Self::from_cells(cells)
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let cell = self.cells.binary_search_by_key(&(y, x), |p| (p.y, p.x));
+                write!(f, "{}", if cell.is_ok() { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Packs `quantities[i]` copies of `shapes[i]` into a `width` by `height` region, deciding
+/// cells in reading order.
+struct Packer {
+    width: i64,
+    height: i64,
+    /// Every orientation of every shape, as the shape's index and its cells relative to its
+    /// first cell in reading order.
+    orientations: Vec<(usize, Vec<(i64, i64)>)>,
+    remaining: Vec<usize>,
+    filled: Vec<bool>,
+    /// How many cells may still be left uncovered.
+    slack: usize,
+    /// How far past the first undecided cell pieces placed so far can reach.
+    reach: usize,
+    /// Partial packings already shown not to work, as the first undecided cell, the cells
+    /// within reach of it and the pieces still to place.
+    dead_ends: HashSet<(usize, Vec<u64>, Vec<usize>)>,
+}
+
+impl Packer {
+    /// The cells orientation `i` would cover with its first cell at `index`, if they are all
+    /// inside the region and free.
+    fn placement(&self, i: usize, index: usize) -> Option<Vec<usize>> {
+        let (x, y) = (index as i64 % self.width, index as i64 / self.width);
+        self.orientations[i]
+            .1
+            .iter()
+            .map(|&(dx, dy)| {
+                let (x, y) = (x + dx, y + dy);
+                let inside = (0..self.width).contains(&x) && (0..self.height).contains(&y);
+                let cell = (y * self.width + x) as usize;
+                (inside && !self.filled[cell]).then_some(cell)
+            })
+            .collect()
+    }
+
+    fn set(&mut self, cells: &[usize], value: bool) {
+        for &cell in cells {
+            self.filled[cell] = value;
+        }
+    }
+
+    fn search(&mut self, from: usize) -> bool {
+        if self.remaining.iter().all(|&n| n == 0) {
+            return true;
+        }
+        let Some(index) = (from..self.filled.len()).find(|&i| !self.filled[i]) else {
+            return false;
+        };
+        let end = (index + self.reach).min(self.filled.len());
+        let mut window = vec![0u64; (end - index).div_ceil(64)];
+        for (i, _) in self.filled[index..end]
+            .iter()
+            .enumerate()
+            .filter(|(_, &f)| f)
+        {
+            window[i / 64] |= 1 << (i % 64);
+        }
+        let key = (index, window, self.remaining.clone());
+        if self.dead_ends.contains(&key) {
+            return false;
+        }
+
+        // Either some piece covers the cell with its first cell...
+        for i in 0..self.orientations.len() {
+            let id = self.orientations[i].0;
+            if self.remaining[id] == 0 {
+                continue;
+            }
+            let Some(cells) = self.placement(i, index) else {
+                continue;
+            };
+            self.set(&cells, true);
+            self.remaining[id] -= 1;
+            if self.search(index + 1) {
+                return true;
+            }
+            self.remaining[id] += 1;
+            self.set(&cells, false);
+        }
+        // ...or it stays empty.
+        if self.slack > 0 {
+            self.slack -= 1;
+            self.filled[index] = true;
+            if self.search(index + 1) {
+                return true;
+            }
+            self.filled[index] = false;
+            self.slack += 1;
+        }
+        self.dead_ends.insert(key);
+        false
+    }
+}
+
+/// Whether `quantities[i]` copies of each `shapes[i]`, in any orientation, fit without
+/// overlapping in a `width` by `height` region. Cells may be left empty. Panics unless there is
+/// exactly one quantity per shape.
+pub fn fits(shapes: &[Shape], quantities: &[usize], width: usize, height: usize) -> bool {
+    assert_eq!(
+        quantities.len(),
+        shapes.len(),
+        "expected one quantity per shape"
+    );
+    let area: usize = shapes
+        .iter()
+        .zip(quantities)
+        .map(|(s, &n)| s.area() * n)
+        .sum();
+    if area > width * height {
+        return false;
+    }
+    let pieces: usize = quantities.iter().sum();
+    if pieces == 0 {
+        return true;
+    }
+    // If every piece fits in its own tile of the region, no search is needed.
+    let (tile_width, tile_height) =
+        shapes
+            .iter()
+            .zip(quantities)
+            .filter(|(_, &n)| n > 0)
+            .fold((0, 0), |(w, h), (s, _)| {
+                let (sw, sh) = (s.width() as usize, s.height() as usize);
+                (w.max(sw.min(sh)), h.max(sw.max(sh)))
+            });
+    let tiles = |w: usize, h: usize| (width / w) * (height / h);
+    if tiles(tile_width, tile_height).max(tiles(tile_height, tile_width)) >= pieces {
+        return true;
+    }
+
+    let orientations = shapes
+        .iter()
+        .enumerate()
+        .flat_map(|(id, shape)| shape.orientations().into_iter().map(move |o| (id, o)))
+        .map(|(id, shape)| {
+            let first = shape.cells()[0];
+            let offsets = shape
+                .cells()
+                .iter()
+                .map(|&p| (p.x - first.x, p.y - first.y))
+                .collect();
+            (id, offsets)
+        })
+        .collect();
+    // Every orientation is tried, so the region can be turned to keep rows short, which keeps
+    // the cells within reach of the first undecided one few and the dead ends reusable.
+    let (width, height) = (width.min(height), width.max(height));
+    let mut packer = Packer {
+        width: width as i64,
+        height: height as i64,
+        orientations,
+        remaining: quantities.to_vec(),
+        filled: vec![false; width * height],
+        slack: width * height - area,
+        reach: width * tile_height,
+        dead_ends: HashSet::new(),
+    };
+    packer.search(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(s: &str) -> Shape {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let l = shape("..\n#.\n#.\n##");
+        assert_eq!(l.to_string(), "#.\n#.\n##\n");
+        assert_eq!((l.width(), l.height(), l.area()), (2, 3, 4));
+        assert_eq!(l.rotate().to_string(), "###\n#..\n");
+        assert_eq!(l.reflect().to_string(), ".#\n.#\n##\n");
+        assert_eq!(
+            "#x".parse::<Shape>(),
+            Err(ParseShapeError::InvalidChar('x'))
+        );
+        assert_eq!("..".parse::<Shape>(), Err(ParseShapeError::Empty));
+    }
+
+    #[test]
+    fn test_orientations() {
+        assert_eq!(shape("#").orientations().len(), 1);
+        assert_eq!(shape("##").orientations().len(), 2);
+        assert_eq!(shape("##\n##").orientations().len(), 1);
+        assert_eq!(shape("#.\n#.\n##").orientations().len(), 8);
+        assert_eq!(shape(".#.\n###").orientations().len(), 4);
+    }
+
+    #[test]
+    fn test_fits() {
+        // The 2025 day 12 example.
+        let shapes: Vec<Shape> = [
+            "###\n##.\n##.",
+            "###\n##.\n.##",
+            ".##\n###\n##.",
+            "##.\n###\n##.",
+            "###\n#..\n###",
+            "###\n.#.\n###",
+        ]
+        .iter()
+        .map(|s| shape(s))
+        .collect();
+        assert!(fits(&shapes, &[0, 0, 0, 0, 2, 0], 4, 4));
+        assert!(fits(&shapes, &[1, 0, 1, 0, 2, 2], 12, 5));
+        assert!(!fits(&shapes, &[1, 0, 1, 0, 3, 2], 12, 5));
+        assert!(!fits(&shapes, &[0, 0, 0, 0, 0, 3], 5, 5));
+        assert!(fits(&shapes, &[9, 0, 0, 0, 0, 0], 9, 9));
+    }
+
+    #[test]
+    #[should_panic(expected = "one quantity per shape")]
+    fn test_fits_quantity_mismatch() {
+        fits(&[shape("#")], &[1, 1], 2, 2);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::grid::Grid;
+use crate::point2d::Point2D;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
+
+type Point = Point2D<i64>;
+
+/// An unbounded grid which only stores occupied cells, for caves and folded paper whose
+/// extent isn't known up front. Keeps the bounding box of the occupied cells up to date.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    /// Cells keyed by `(y, x)` so a row is a contiguous range.
+    rows: BTreeMap<(i64, i64), T>,
+    /// The same positions keyed by `(x, y)` so a column is a contiguous range.
+    columns: BTreeSet<(i64, i64)>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            columns: BTreeSet::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.rows.get(&(point.y, point.x))
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.rows.get_mut(&(point.y, point.x))
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.rows.contains_key(&(point.y, point.x))
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.columns.insert((point.x, point.y));
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.component_min(point), max.component_max(point)),
+            None => (point, point),
+        });
+        self.rows.insert((point.y, point.x), value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let removed = self.rows.remove(&(point.y, point.x))?;
+        self.columns.remove(&(point.x, point.y));
+        if let Some((min, max)) = self.bounds {
+            if point.x == min.x || point.y == min.y || point.x == max.x || point.y == max.y {
+                self.bounds = self.compute_bounds();
+            }
+        }
+        Some(removed)
+    }
+
+    fn compute_bounds(&self) -> Option<(Point, Point)> {
+        let (&(min_y, _), _) = self.rows.first_key_value()?;
+        let (&(max_y, _), _) = self.rows.last_key_value()?;
+        let &(min_x, _) = self.columns.first()?;
+        let &(max_x, _) = self.columns.last()?;
+        Some((Point2D::new(min_x, min_y), Point2D::new(max_x, max_y)))
+    }
+
+    /// The smallest and largest corner of the box enclosing every occupied cell.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.rows
+            .iter()
+            .map(|(&(y, x), value)| (Point2D::new(x, y), value))
+    }
+
+    /// Occupied cells in row `y` with `x` in `xs`, in increasing `x`.
+    pub fn row_range(&self, y: i64, xs: RangeInclusive<i64>) -> impl Iterator<Item = (Point, &T)> {
+        self.rows
+            .range((y, *xs.start())..=(y, *xs.end()))
+            .map(|(&(y, x), value)| (Point2D::new(x, y), value))
+    }
+
+    /// Occupied cells in column `x` with `y` in `ys`, in increasing `y`.
+    pub fn column_range(
+        &self,
+        x: i64,
+        ys: RangeInclusive<i64>,
+    ) -> impl Iterator<Item = (Point, &T)> {
+        self.columns
+            .range((x, *ys.start())..=(x, *ys.end()))
+            .map(|&(x, y)| (Point2D::new(x, y), &self.rows[&(y, x)]))
+    }
+
+    /// A dense copy of the bounding box, with `fill` where nothing is stored, and the
+    /// position of the grid's top left corner.
+    pub fn to_grid(&self, fill: T) -> Option<(Point, Grid<T>)>
+    where
+        T: Clone,
+    {
+        let (min, max) = self.bounds?;
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let mut grid = Grid::new(width, height, fill);
+        for (point, value) in self.iter() {
+            let offset = point - min;
+            grid[Point2D::new(offset.x as usize, offset.y as usize)] = value.clone();
+        }
+        Some((min, grid))
+    }
+
+    /// The cells of `grid` for which `keep` holds, with the grid's top left at `origin`.
+    pub fn from_grid(grid: &Grid<T>, origin: Point, keep: impl Fn(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        let mut sparse = Self::new();
+        for (point, value) in grid.iter() {
+            if keep(value) {
+                let offset = Point2D::new(point.x as i64, point.y as i64);
+                sparse.insert(origin + offset, value.clone());
+            }
+        }
+        sparse
+    }
+
+    /// Draws the rectangle with inclusive corners `min` and `max`, one line per row, mapping
+    /// each cell to a character with `to_char`.
+    pub fn render_region(
+        &self,
+        min: Point,
+        max: Point,
+        to_char: impl Fn(Option<&T>) -> char,
+    ) -> String {
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| to_char(self.get(Point2D::new(x, y))))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draws the bounding box of the occupied cells.
+    pub fn render(&self, to_char: impl Fn(Option<&T>) -> char) -> String {
+        match self.bounds {
+            Some((min, max)) => self.render_region(min, max, to_char),
+            None => String::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut sparse = Self::new();
+        for (point, value) in iter {
+            sparse.insert(point, value);
+        }
+        sparse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut sparse = SparseGrid::new();
+        assert_eq!(sparse.bounds(), None);
+        sparse.insert(Point2D::new(2, 3), 'a');
+        sparse.insert(Point2D::new(-1, 5), 'b');
+        sparse.insert(Point2D::new(0, 4), 'c');
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point2D::new(-1, 3), Point2D::new(2, 5)))
+        );
+        assert_eq!(sparse.remove(Point2D::new(-1, 5)), Some('b'));
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point2D::new(0, 3), Point2D::new(2, 4)))
+        );
+        sparse.remove(Point2D::new(2, 3));
+        sparse.remove(Point2D::new(0, 4));
+        assert_eq!(sparse.bounds(), None);
+        assert!(sparse.is_empty());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let sparse: SparseGrid<u8> = [(0, 0), (3, 0), (5, 0), (3, 2), (3, 9)]
+            .into_iter()
+            .map(|(x, y)| (Point2D::new(x, y), 0))
+            .collect();
+        let xs: Vec<i64> = sparse.row_range(0, 1..=5).map(|(p, _)| p.x).collect();
+        assert_eq!(xs, vec![3, 5]);
+        let ys: Vec<i64> = sparse
+            .column_range(3, 1..=i64::MAX)
+            .map(|(p, _)| p.y)
+            .collect();
+        assert_eq!(ys, vec![2, 9]);
+    }
+
+    #[test]
+    fn test_grid_round_trip() {
+        let sparse: SparseGrid<char> = [((5, -2), '#'), ((7, -1), '#')]
+            .into_iter()
+            .map(|((x, y), c)| (Point2D::new(x, y), c))
+            .collect();
+        let (origin, grid) = sparse.to_grid('.').unwrap();
+        assert_eq!(origin, Point2D::new(5, -2));
+        assert_eq!(grid.to_string(), "#..\n..#\n");
+        assert_eq!(SparseGrid::from_grid(&grid, origin, |&c| c == '#'), sparse);
+        assert_eq!(sparse.render(|c| c.map_or(' ', |_| 'o')), "o  \n  o");
+    }
+}
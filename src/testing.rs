@@ -0,0 +1,368 @@
+//! Randomised checks for parser types: that parsing what `Display` prints gives the value
+//! back, and that parsers reject garbage without panicking. Runs are seeded, so a failure
+//! reproduces exactly.
+
+use crate::point2d::Point2D;
+use crate::point3d::Point3D;
+use std::fmt::{Debug, Display};
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A small deterministic generator (SplitMix64).
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`. Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        self.next_u64() % n
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let span = range.end().wrapping_sub(*range.start()) as u64;
+        match span.checked_add(1) {
+            Some(n) => range.start().wrapping_add(self.below(n) as i64),
+            None => self.next_u64() as i64,
+        }
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    /// Up to `max_len` items from `f`.
+    pub fn vec<T>(&mut self, max_len: usize, mut f: impl FnMut(&mut Self) -> T) -> Vec<T> {
+        let len = self.below(max_len as u64 + 1) as usize;
+        (0..len).map(|_| f(self)).collect()
+    }
+}
+
+/// Types with a default way to generate random values. `size` grows over a run and bounds
+/// lengths and magnitudes, so early failures tend to be small.
+pub trait Arbitrary: Sized {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self;
+}
+
+macro_rules! arbitrary_int {
+    ($($t:ty),*) => {
+        $(impl Arbitrary for $t {
+            fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+                // Mostly small numbers, with the extremes now and then.
+                match rng.below(8) {
+                    0 => <$t>::MIN,
+                    1 => <$t>::MAX,
+                    _ => {
+                        let bound = (size as i64).saturating_mul(size as i64);
+                        let low = i64::try_from(<$t>::MIN).unwrap_or(i64::MIN).max(-bound);
+                        let high = i64::try_from(<$t>::MAX).unwrap_or(i64::MAX).min(bound);
+                        rng.range(low..=high) as $t
+                    }
+                }
+            }
+        })*
+    };
+}
+
+arbitrary_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng, _size: usize) -> Self {
+        rng.bool()
+    }
+}
+
+impl Arbitrary for char {
+    fn arbitrary(rng: &mut Rng, _size: usize) -> Self {
+        match rng.below(10) {
+            0 => *rng.choose(&['é', 'λ', '中', '🎄', '\0', '\t']),
+            1 => *rng.choose(&['\n', ' ', '-', ',', '[', ']', ':', '.']),
+            _ => rng.range(0x20..=0x7e) as u8 as char,
+        }
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        rng.vec(size, |rng| char::arbitrary(rng, size))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        rng.vec(size, |rng| T::arbitrary(rng, size))
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        rng.bool().then(|| T::arbitrary(rng, size))
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary> Arbitrary for (A, B) {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        (A::arbitrary(rng, size), B::arbitrary(rng, size))
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Point2D<T> {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        Point2D::new(T::arbitrary(rng, size), T::arbitrary(rng, size))
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Point3D<T> {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        Point3D::new(
+            T::arbitrary(rng, size),
+            T::arbitrary(rng, size),
+            T::arbitrary(rng, size),
+        )
+    }
+}
+
+/// Runs randomised checks, panicking with a reproducible report on the first failure.
+#[derive(Copy, Clone, Debug)]
+pub struct Checker {
+    pub cases: usize,
+    pub seed: u64,
+    /// The `size` reached by the last case.
+    pub max_size: usize,
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self {
+            cases: 256,
+            seed: 0x5eed,
+            max_size: 32,
+        }
+    }
+}
+
+impl Checker {
+    fn sizes(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.cases).map(|case| (case, 1 + case * self.max_size / self.cases.max(1)))
+    }
+
+    /// Checks `parse(x.to_string()) == Some(x)` for values from `generate`, then that `parse`
+    /// survives mangled versions of those strings.
+    pub fn roundtrip<T, G, P>(&self, mut generate: G, parse: P)
+    where
+        T: Debug + Display + PartialEq,
+        G: FnMut(&mut Rng, usize) -> T,
+        P: Fn(&str) -> Option<T>,
+    {
+        let mut rng = Rng::new(self.seed);
+        let mut samples = Vec::with_capacity(self.cases);
+        for (case, size) in self.sizes() {
+            let value = generate(&mut rng, size);
+            let text = value.to_string();
+            let parsed = catch(&parse, &text).unwrap_or_else(|message| {
+                panic!(
+                    "case {case} (seed {:#x}): parser panicked on {text:?}: {message}",
+                    self.seed
+                )
+            });
+            if parsed.as_ref() != Some(&value) {
+                panic!(
+                    "case {case} (seed {:#x}): {value:?} printed as {text:?} but parsed as {parsed:?}",
+                    self.seed
+                );
+            }
+            samples.push(text);
+        }
+        self.never_panics(&samples, parse);
+    }
+
+    /// Feeds `parse` random strings and mutations of `samples`, failing with the shortest
+    /// input found which makes it panic.
+    pub fn never_panics<T, P>(&self, samples: &[String], parse: P)
+    where
+        P: Fn(&str) -> Option<T>,
+    {
+        let mut rng = Rng::new(self.seed ^ 0xbad);
+        for (case, size) in self.sizes() {
+            let input = if samples.is_empty() || rng.below(4) == 0 {
+                String::arbitrary(&mut rng, size)
+            } else {
+                let sample = rng.choose(samples).clone();
+                mutate(&mut rng, sample, size)
+            };
+            if let Err(message) = catch(&parse, &input) {
+                let input = shrink(&parse, input);
+                panic!(
+                    "case {case} (seed {:#x}): parser panicked on {input:?}: {message}",
+                    self.seed
+                );
+            }
+        }
+    }
+}
+
+/// Runs `parse`, turning a panic into its message.
+fn catch<T>(parse: impl Fn(&str) -> Option<T>, input: &str) -> Result<Option<T>, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| parse(input))).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default()
+    })
+}
+
+/// Deletes, duplicates, swaps or inserts a few characters.
+fn mutate(rng: &mut Rng, text: String, size: usize) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    for _ in 0..=rng.below(3) {
+        let at = rng.below(chars.len() as u64 + 1) as usize;
+        match rng.below(4) {
+            0 if at < chars.len() => {
+                chars.remove(at);
+            }
+            1 if at < chars.len() => chars.insert(at, chars[at]),
+            2 if at + 1 < chars.len() => chars.swap(at, at + 1),
+            _ => chars.insert(at, char::arbitrary(rng, size)),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Removes characters from a panicking input for as long as it still panics.
+fn shrink<T>(parse: impl Fn(&str) -> Option<T>, input: String) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut shorter = chars.clone();
+        shorter.remove(i);
+        if catch(&parse, &shorter.iter().collect::<String>()).is_err() {
+            chars = shorter;
+        } else {
+            i += 1;
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Defines a test checking that `$parse` reads back whatever `$generate` produces, printed
+/// with `Display`, and never panics on mangled input.
+///
+/// ```ignore
+/// roundtrip!(test_roundtrip, Instruction::arbitrary, |s| s.parse().ok());
+/// ```
+#[macro_export]
+macro_rules! roundtrip {
+    ($name:ident, $generate:expr, $parse:expr) => {
+        #[test]
+        fn $name() {
+            $crate::testing::Checker::default().roundtrip($generate, $parse);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Move(Point2D<i64>, bool);
+
+    impl Display for Move {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let verb = if self.1 { "jump" } else { "walk" };
+            write!(f, "{verb} {},{}", self.0.x, self.0.y)
+        }
+    }
+
+    fn parse_move(s: &str) -> Option<Move> {
+        let (verb, point) = s.split_once(' ')?;
+        let jump = match verb {
+            "jump" => true,
+            "walk" => false,
+            _ => return None,
+        };
+        Some(Move(point.parse().ok()?, jump))
+    }
+
+    roundtrip!(
+        test_roundtrip_macro,
+        |rng, size| Move(Point2D::arbitrary(rng, size), rng.bool()),
+        parse_move
+    );
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(1);
+        let mut b = Rng::new(1);
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert!((0..1000).all(|_| (-3..=3).contains(&a.range(-3..=3))));
+        a.range(i64::MIN..=i64::MAX);
+    }
+
+    #[test]
+    fn test_arbitrary_ints() {
+        let mut rng = Rng::new(1);
+        let small = |n: u64| n <= 9;
+        let usizes: Vec<_> = (0..100).map(|_| usize::arbitrary(&mut rng, 3)).collect();
+        assert!(usizes.iter().filter(|&&n| small(n as u64)).count() > 50);
+        assert!(usizes.iter().all(|&n| small(n as u64) || n == usize::MAX));
+        let u64s: Vec<_> = (0..100).map(|_| u64::arbitrary(&mut rng, 3)).collect();
+        assert!(u64s.iter().all(|&n| small(n) || n == u64::MAX));
+    }
+
+    /// Prints with brackets which its parser doesn't accept.
+    #[derive(Debug, PartialEq)]
+    struct Bracketed(u8);
+
+    impl Display for Bracketed {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "[{}]", self.0)
+        }
+    }
+
+    #[test]
+    fn test_catches_bugs() {
+        let report = panic::catch_unwind(|| {
+            Checker::default().roundtrip(
+                |rng, size| Bracketed(u8::arbitrary(rng, size)),
+                |s| s.parse().ok().map(Bracketed),
+            )
+        })
+        .unwrap_err();
+        assert!(report
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("parsed as None"));
+
+        let report = panic::catch_unwind(|| {
+            Checker::default().never_panics(&["12".to_string()], |s| {
+                assert!(!s.contains('9'), "nines");
+                Some(s.len())
+            })
+        })
+        .unwrap_err();
+        assert!(report
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("on \"9\""));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+/// A handle to a node of a `Tree`. Only meaningful for the tree which created it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NodeId(usize);
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    value: T,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/// An ordered tree stored in one arena and navigated by `NodeId`, so nodes can point at their
+/// parent without `Rc<RefCell<_>>`. Detached nodes stay in the arena until the tree is dropped.
+#[derive(Clone, Debug)]
+pub struct Tree<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T> Tree<T> {
+    pub fn new(root: T) -> Self {
+        Self {
+            nodes: vec![Node {
+                value: root,
+                parent: None,
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// The number of nodes ever added, including detached ones.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Appends a new last child to `parent`.
+    pub fn add_child(&mut self, parent: NodeId, value: T) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            value,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    pub fn is_leaf(&self, id: NodeId) -> bool {
+        self.nodes[id.0].children.is_empty()
+    }
+
+    /// The children of `id`'s parent and `id`'s position among them.
+    fn siblings(&self, id: NodeId) -> Option<(&[NodeId], usize)> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|&sibling| sibling == id)?;
+        Some((siblings, index))
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let (siblings, index) = self.siblings(id)?;
+        siblings.get(index + 1).copied()
+    }
+
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let (siblings, index) = self.siblings(id)?;
+        Some(siblings[index.checked_sub(1)?])
+    }
+
+    /// The parent, grandparent and so on up to the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), |&node| self.parent(node))
+    }
+
+    /// The number of edges between `id` and the root.
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count()
+    }
+
+    /// The subtree under `id`, each node before its children.
+    pub fn pre_order(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        let mut stack = vec![id];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            stack.extend(self.children(node).iter().rev());
+        }
+        order
+    }
+
+    /// The subtree under `id`, each node after its children.
+    pub fn post_order(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = self.mirrored_pre_order(id);
+        order.reverse();
+        order
+    }
+
+    fn mirrored_pre_order(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        let mut stack = vec![id];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            stack.extend(self.children(node));
+        }
+        order
+    }
+
+    /// The subtree under `id` with each node after its first child's subtree and before the
+    /// rest, which for binary trees is the usual left, node, right order.
+    pub fn in_order(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        self.in_order_into(id, &mut order);
+        order
+    }
+
+    fn in_order_into(&self, id: NodeId, order: &mut Vec<NodeId>) {
+        let children = self.children(id);
+        match children.split_first() {
+            Some((&first, rest)) => {
+                self.in_order_into(first, order);
+                order.push(id);
+                for &child in rest {
+                    self.in_order_into(child, order);
+                }
+            }
+            None => order.push(id),
+        }
+    }
+
+    /// Combines the subtree under `id` bottom up, passing each node's value and the results
+    /// for its children to `f`.
+    pub fn fold<A>(&self, id: NodeId, mut f: impl FnMut(&T, &[A]) -> A) -> A {
+        let mut results: HashMap<NodeId, A> = HashMap::new();
+        for node in self.post_order(id) {
+            let children: Vec<A> = self
+                .children(node)
+                .iter()
+                .map(|child| results.remove(child).unwrap())
+                .collect();
+            results.insert(node, f(&self[node], &children));
+        }
+        results.remove(&id).unwrap()
+    }
+
+    /// Like `fold`, but keeps the result for every node in the subtree.
+    pub fn fold_all<A: Clone>(
+        &self,
+        id: NodeId,
+        mut f: impl FnMut(&T, &[A]) -> A,
+    ) -> HashMap<NodeId, A> {
+        let mut results: HashMap<NodeId, A> = HashMap::new();
+        for node in self.post_order(id) {
+            let children: Vec<A> = self
+                .children(node)
+                .iter()
+                .map(|child| results[child].clone())
+                .collect();
+            results.insert(node, f(&self[node], &children));
+        }
+        results
+    }
+
+    /// Replaces the value at `id`, returning the old one.
+    pub fn replace(&mut self, id: NodeId, value: T) -> T {
+        std::mem::replace(&mut self.nodes[id.0].value, value)
+    }
+
+    /// Replaces the value at `id` and detaches all of its descendants, turning it into a leaf.
+    pub fn replace_subtree(&mut self, id: NodeId, value: T) -> T {
+        for child in std::mem::take(&mut self.nodes[id.0].children) {
+            self.nodes[child.0].parent = None;
+        }
+        self.replace(id, value)
+    }
+
+    /// Copies the subtree under `from` in `other` to be the new last child of `parent`.
+    pub fn graft(&mut self, parent: NodeId, other: &Tree<T>, from: NodeId) -> NodeId
+    where
+        T: Clone,
+    {
+        let id = self.add_child(parent, other[from].clone());
+        for &child in other.children(from) {
+            self.graft(id, other, child);
+        }
+        id
+    }
+}
+
+/// Trees are equal when their shapes and values match, however their arenas are laid out.
+impl<T: PartialEq> PartialEq for Tree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        fn shape<T>(tree: &Tree<T>) -> Vec<(&T, usize)> {
+            tree.pre_order(tree.root())
+                .into_iter()
+                .map(|id| (&tree[id], tree.children(id).len()))
+                .collect()
+        }
+        shape(self) == shape(other)
+    }
+}
+
+impl<T: Eq> Eq for Tree<T> {}
+
+impl<T> Index<NodeId> for Tree<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId) -> &T {
+        &self.nodes[id.0].value
+    }
+}
+
+impl<T> IndexMut<NodeId> for Tree<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut T {
+        &mut self.nodes[id.0].value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `a` with children `b` (which has `d` and `e`) and `c`.
+    fn example() -> (Tree<char>, [NodeId; 5]) {
+        let mut tree = Tree::new('a');
+        let a = tree.root();
+        let b = tree.add_child(a, 'b');
+        let c = tree.add_child(a, 'c');
+        let d = tree.add_child(b, 'd');
+        let e = tree.add_child(b, 'e');
+        (tree, [a, b, c, d, e])
+    }
+
+    fn values(tree: &Tree<char>, order: Vec<NodeId>) -> String {
+        order.into_iter().map(|id| tree[id]).collect()
+    }
+
+    #[test]
+    fn test_navigation() {
+        let (tree, [a, b, c, d, e]) = example();
+        assert_eq!(tree.parent(d), Some(b));
+        assert_eq!(tree.parent(a), None);
+        assert_eq!(tree.children(b), &[d, e]);
+        assert_eq!(tree.next_sibling(b), Some(c));
+        assert_eq!(tree.next_sibling(c), None);
+        assert_eq!(tree.previous_sibling(e), Some(d));
+        assert_eq!(tree.previous_sibling(a), None);
+        assert_eq!(tree.ancestors(e).collect::<Vec<_>>(), vec![b, a]);
+        assert_eq!(tree.depth(e), 2);
+        assert!(tree.is_leaf(c));
+    }
+
+    #[test]
+    fn test_traversals() {
+        let (tree, [a, b, ..]) = example();
+        assert_eq!(values(&tree, tree.pre_order(a)), "abdec");
+        assert_eq!(values(&tree, tree.in_order(a)), "dbeac");
+        assert_eq!(values(&tree, tree.post_order(a)), "debca");
+        assert_eq!(values(&tree, tree.post_order(b)), "deb");
+    }
+
+    #[test]
+    fn test_fold() {
+        let (tree, [a, b, ..]) = example();
+        let count = |_: &char, children: &[usize]| 1 + children.iter().sum::<usize>();
+        assert_eq!(tree.fold(a, count), 5);
+        let sizes = tree.fold_all(a, count);
+        assert_eq!(sizes[&b], 3);
+        assert_eq!(sizes.len(), 5);
+    }
+
+    #[test]
+    fn test_replace_and_graft() {
+        let (mut tree, [a, b, c, ..]) = example();
+        assert_eq!(tree.replace_subtree(b, 'x'), 'b');
+        assert_eq!(values(&tree, tree.pre_order(a)), "axc");
+        tree[c] = 'y';
+        let (other, [_, other_b, ..]) = example();
+        let grafted = tree.graft(c, &other, other_b);
+        assert_eq!(tree.parent(grafted), Some(c));
+        assert_eq!(values(&tree, tree.pre_order(a)), "axybde");
+
+        // Detached nodes left in the arena don't affect equality.
+        let mut fresh = Tree::new('a');
+        let root = fresh.root();
+        fresh.add_child(root, 'x');
+        let y = fresh.add_child(root, 'y');
+        assert_ne!(tree, fresh);
+        fresh.graft(y, &other, other_b);
+        assert_eq!(tree, fresh);
+    }
+}
@@ -0,0 +1,283 @@
+use crate::grid::Grid;
+use crate::point2d::Point2D;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufWriter, Stderr, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Colour {
+    #[default]
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Grey,
+}
+
+impl Colour {
+    fn ansi_code(self) -> Option<u8> {
+        match self {
+            Colour::Default => None,
+            Colour::Red => Some(31),
+            Colour::Green => Some(32),
+            Colour::Yellow => Some(33),
+            Colour::Blue => Some(34),
+            Colour::Magenta => Some(35),
+            Colour::Cyan => Some(36),
+            Colour::Grey => Some(90),
+        }
+    }
+}
+
+/// A picture of some puzzle state as rows of coloured characters, with an optional caption.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    width: usize,
+    cells: Vec<(char, Colour)>,
+    caption: Option<String>,
+}
+
+impl Frame {
+    /// A blank frame.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            cells: vec![(' ', Colour::Default); width * height],
+            caption: None,
+        }
+    }
+
+    /// Draws every cell of `grid` with `draw`.
+    pub fn from_grid<T>(
+        grid: &Grid<T>,
+        mut draw: impl FnMut(Point2D<usize>, &T) -> (char, Colour),
+    ) -> Self {
+        Self {
+            width: grid.width(),
+            cells: grid.iter().map(|(p, cell)| draw(p, cell)).collect(),
+            caption: None,
+        }
+    }
+
+    /// One line of text per row, in the default colour.
+    pub fn from_text(text: &str) -> Self {
+        let width = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut frame = Frame::new(width, text.lines().count());
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                frame.set(Point2D::new(x, y), c, Colour::Default);
+            }
+        }
+        frame
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    pub fn get(&self, p: Point2D<usize>) -> Option<(char, Colour)> {
+        (p.x < self.width)
+            .then(|| self.cells.get(p.y * self.width + p.x).copied())
+            .flatten()
+    }
+
+    /// Panics if `p` is outside the frame.
+    pub fn set(&mut self, p: Point2D<usize>, c: char, colour: Colour) {
+        assert!(
+            p.x < self.width && p.y < self.height(),
+            "{p:?} is outside the frame"
+        );
+        self.cells[p.y * self.width + p.x] = (c, colour);
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[(char, Colour)]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The frame with ANSI escapes for its colours, for a terminal.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        if let Some(caption) = &self.caption {
+            out.push_str(caption);
+            out.push('\n');
+        }
+        for row in self.rows() {
+            for &(c, colour) in row {
+                match colour.ansi_code() {
+                    Some(code) => out.push_str(&format!("\x1b[{code}m{c}\x1b[0m")),
+                    None => out.push(c),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The frame as plain text, without colours.
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(caption) = &self.caption {
+            writeln!(f, "{caption}")?;
+        }
+        for row in self.rows() {
+            for &(c, _) in row {
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Somewhere frames go.
+pub trait Sink {
+    /// Whether frames are wanted at all, so callers can skip building them.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn frame(&mut self, frame: &Frame) -> io::Result<()>;
+}
+
+/// Discards every frame.
+pub struct Null;
+
+impl Sink for Null {
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    fn frame(&mut self, _frame: &Frame) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes frames one after another, separated by blank lines.
+pub struct Writer<W> {
+    out: W,
+    colour: bool,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out: W, colour: bool) -> Self {
+        Self { out, colour }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl Writer<Stderr> {
+    /// Coloured frames on stderr, out of the way of the answers on stdout.
+    pub fn terminal() -> Self {
+        Self::new(io::stderr(), true)
+    }
+}
+
+impl Writer<BufWriter<File>> {
+    /// Plain text frames in a new file at `path`.
+    pub fn file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), false))
+    }
+}
+
+impl<W: Write> Sink for Writer<W> {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        if self.colour {
+            writeln!(self.out, "{}", frame.to_ansi())?;
+        } else {
+            writeln!(self.out, "{frame}")?;
+        }
+        self.out.flush()
+    }
+}
+
+static SINK: Mutex<Option<Box<dyn Sink + Send>>> = Mutex::new(None);
+
+/// Sends every later frame to `sink`, replacing the default which drops them.
+pub fn set_sink(sink: Box<dyn Sink + Send>) {
+    *SINK.lock().unwrap() = Some(sink);
+}
+
+/// Whether a sink wants frames.
+pub fn enabled() -> bool {
+    SINK.lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|sink| sink.enabled())
+}
+
+/// Passes the frame from `draw` to the current sink. `draw` only runs if a sink wants it, so
+/// solutions can call this freely.
+pub fn emit(draw: impl FnOnce() -> Frame) {
+    let mut sink = SINK.lock().unwrap();
+    if let Some(sink) = sink.as_mut().filter(|sink| sink.enabled()) {
+        if let Err(e) = sink.frame(&draw()) {
+            eprintln!("Unable to write visualization frame: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let frame = Frame::from_grid(&grid, |p, &n| {
+            let colour = if p.x == p.y {
+                Colour::Red
+            } else {
+                Colour::Default
+            };
+            (char::from_digit(n, 10).unwrap(), colour)
+        })
+        .with_caption("step 1");
+        assert_eq!((frame.width(), frame.height()), (2, 2));
+        assert_eq!(frame.get(Point2D::new(1, 1)), Some(('4', Colour::Red)));
+        assert_eq!(frame.get(Point2D::new(2, 0)), None);
+        assert_eq!(frame.to_string(), "step 1\n12\n34\n");
+        assert_eq!(
+            frame.to_ansi(),
+            "step 1\n\x1b[31m1\x1b[0m2\n3\x1b[31m4\x1b[0m\n"
+        );
+        assert_eq!(Frame::from_text("ab\nc").to_string(), "ab\nc \n");
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut writer = Writer::new(Vec::new(), false);
+        assert!(writer.enabled() && !Null.enabled());
+        writer.frame(&Frame::from_text("#.")).unwrap();
+        writer.frame(&Frame::from_text(".#")).unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "#.\n\n.#\n\n"
+        );
+    }
+}
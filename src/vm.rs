@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub type Word = i64;
+
+/// What the machine does after an instruction has executed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Control {
+    /// Continue with the following instruction.
+    Next,
+    /// Move the program counter by a signed offset.
+    Jump(Word),
+    /// Move the program counter to an absolute address.
+    JumpTo(usize),
+    Halt,
+}
+
+/// The registers and output of a machine, which is all an instruction may touch.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct State {
+    registers: BTreeMap<String, Word>,
+    pub output: Vec<Word>,
+}
+
+impl State {
+    /// The value of a register, treating ones never written as zero.
+    pub fn get(&self, register: &str) -> Word {
+        self.registers.get(register).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, register: &str, value: Word) {
+        match self.registers.get_mut(register) {
+            Some(slot) => *slot = value,
+            None => {
+                self.registers.insert(register.to_string(), value);
+            }
+        }
+    }
+
+    pub fn update(&mut self, register: &str, f: impl FnOnce(Word) -> Word) {
+        let value = f(self.get(register));
+        self.set(register, value);
+    }
+
+    pub fn registers(&self) -> impl Iterator<Item = (&str, Word)> {
+        self.registers
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+    }
+}
+
+/// One opcode of a puzzle's assembly language.
+pub trait Instruction {
+    /// How many clock cycles the instruction takes to complete.
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, state: &mut State) -> Control;
+}
+
+/// Why a run stopped.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Stop {
+    /// The program counter left the program or an instruction halted.
+    Halted,
+    /// The next instruction to execute is at a breakpoint.
+    Breakpoint(usize),
+    /// The condition given to `run_until` held.
+    Condition,
+    /// The machine is about to repeat a state it was in `period` instructions ago, so it will
+    /// never halt.
+    Loop { pc: usize, period: usize },
+}
+
+/// Executes a program of `I`s, counting instructions and cycles.
+pub struct Machine<I> {
+    program: Vec<I>,
+    state: State,
+    pc: usize,
+    cycle: usize,
+    steps: usize,
+    halted: bool,
+    breakpoints: HashSet<usize>,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>) -> Self {
+        Self {
+            program,
+            state: State::default(),
+            pc: 0,
+            cycle: 0,
+            steps: 0,
+            halted: false,
+            breakpoints: HashSet::new(),
+        }
+    }
+
+    pub fn with_register(mut self, register: &str, value: Word) -> Self {
+        self.state.set(register, value);
+        self
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    pub fn register(&self, register: &str) -> Word {
+        self.state.get(register)
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The number of cycles completed so far.
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// The number of instructions executed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted || self.pc >= self.program.len()
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    /// Executes one instruction, calling `hook` with the number of each cycle it spans and
+    /// the state during that cycle, i.e. before the instruction takes effect. Returns false
+    /// if the machine had already halted.
+    pub fn step_with(&mut self, mut hook: impl FnMut(usize, &State)) -> bool {
+        if self.is_halted() {
+            return false;
+        }
+        let instruction = &self.program[self.pc];
+        for _ in 0..instruction.cycles() {
+            self.cycle += 1;
+            hook(self.cycle, &self.state);
+        }
+        let control = instruction.execute(&mut self.state);
+        self.steps += 1;
+        match control {
+            Control::Next => self.pc += 1,
+            Control::Jump(offset) => match self.pc.checked_add_signed(offset as isize) {
+                Some(pc) => self.pc = pc,
+                None => self.halted = true,
+            },
+            Control::JumpTo(pc) => self.pc = pc,
+            Control::Halt => self.halted = true,
+        }
+        true
+    }
+
+    pub fn step(&mut self) -> bool {
+        self.step_with(|_, _| ())
+    }
+
+    /// Runs until the machine halts or reaches a breakpoint, calling `hook` every cycle. A
+    /// breakpoint at the current instruction is skipped so a stopped run can be resumed.
+    pub fn run_with(&mut self, mut hook: impl FnMut(usize, &State)) -> Stop {
+        let mut first = true;
+        while !self.is_halted() {
+            if !first && self.breakpoints.contains(&self.pc) {
+                return Stop::Breakpoint(self.pc);
+            }
+            first = false;
+            self.step_with(&mut hook);
+        }
+        Stop::Halted
+    }
+
+    pub fn run(&mut self) -> Stop {
+        self.run_with(|_, _| ())
+    }
+
+    /// Runs until `condition` holds before an instruction, the machine halts or it reaches a
+    /// breakpoint.
+    pub fn run_until(&mut self, mut condition: impl FnMut(&Self) -> bool) -> Stop {
+        let mut first = true;
+        while !self.is_halted() {
+            if condition(self) {
+                return Stop::Condition;
+            }
+            if !first && self.breakpoints.contains(&self.pc) {
+                return Stop::Breakpoint(self.pc);
+            }
+            first = false;
+            self.step();
+        }
+        Stop::Halted
+    }
+
+    /// Runs like `run`, but also stops when the program counter and registers repeat, since
+    /// the machine would then loop forever. Output is not part of the compared state.
+    pub fn run_detecting_loop(&mut self) -> Stop {
+        let mut seen: HashMap<(usize, BTreeMap<String, Word>), usize> = HashMap::new();
+        let mut first = true;
+        while !self.is_halted() {
+            if !first && self.breakpoints.contains(&self.pc) {
+                return Stop::Breakpoint(self.pc);
+            }
+            first = false;
+            let key = (self.pc, self.state.registers.clone());
+            if let Some(&previous) = seen.get(&key) {
+                return Stop::Loop {
+                    pc: self.pc,
+                    period: self.steps - previous,
+                };
+            }
+            seen.insert(key, self.steps);
+            self.step();
+        }
+        Stop::Halted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Crt {
+        Noop,
+        Addx(Word),
+    }
+
+    impl Instruction for Crt {
+        fn cycles(&self) -> usize {
+            match self {
+                Crt::Noop => 1,
+                Crt::Addx(_) => 2,
+            }
+        }
+
+        fn execute(&self, state: &mut State) -> Control {
+            if let Crt::Addx(value) = self {
+                state.update("x", |x| x + value);
+            }
+            Control::Next
+        }
+    }
+
+    enum Lock {
+        Inc(&'static str),
+        Tpl(&'static str),
+        Jmp(Word),
+        Jio(&'static str, Word),
+        Out(&'static str),
+    }
+
+    impl Instruction for Lock {
+        fn execute(&self, state: &mut State) -> Control {
+            match *self {
+                Lock::Inc(r) => state.update(r, |v| v + 1),
+                Lock::Tpl(r) => state.update(r, |v| v * 3),
+                Lock::Jmp(offset) => return Control::Jump(offset),
+                Lock::Jio(r, offset) if state.get(r) == 1 => return Control::Jump(offset),
+                Lock::Jio(..) => (),
+                Lock::Out(r) => state.output.push(state.get(r)),
+            }
+            Control::Next
+        }
+    }
+
+    #[test]
+    fn test_cycles() {
+        let program = vec![Crt::Noop, Crt::Addx(3), Crt::Addx(-5)];
+        let mut machine = Machine::new(program).with_register("x", 1);
+        let mut during = Vec::new();
+        assert_eq!(
+            machine.run_with(|cycle, state| during.push((cycle, state.get("x")))),
+            Stop::Halted
+        );
+        assert_eq!(during, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(machine.register("x"), -1);
+        assert_eq!(machine.cycle(), 5);
+        assert_eq!(machine.steps(), 3);
+        assert!(!machine.step());
+    }
+
+    #[test]
+    fn test_jumps_and_breakpoints() {
+        // The 2015 day 23 example, with an output instruction appended.
+        let program = vec![
+            Lock::Inc("a"),
+            Lock::Jio("a", 2),
+            Lock::Tpl("a"),
+            Lock::Inc("a"),
+            Lock::Out("a"),
+        ];
+        let mut machine = Machine::new(program);
+        machine.add_breakpoint(3);
+        assert_eq!(machine.run(), Stop::Breakpoint(3));
+        assert_eq!(machine.register("a"), 1);
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.state().output, vec![2]);
+
+        let mut machine = Machine::new(vec![Lock::Inc("a"), Lock::Inc("a"), Lock::Inc("a")]);
+        assert_eq!(machine.run_until(|m| m.register("a") == 2), Stop::Condition);
+        assert_eq!(machine.pc(), 2);
+    }
+
+    #[test]
+    fn test_loop_detection() {
+        let program = vec![Lock::Inc("a"), Lock::Jmp(1), Lock::Jmp(-1)];
+        let mut machine = Machine::new(program);
+        assert_eq!(
+            machine.run_detecting_loop(),
+            Stop::Loop { pc: 1, period: 2 }
+        );
+        assert_eq!(machine.register("a"), 1);
+    }
+}